@@ -0,0 +1,222 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use muscleman::Buffer::Buffer;
+use muscleman::ByteOrder::ByteOrder;
+
+// The number of values written or read per iteration
+const COUNT: usize = 1000;
+
+// The read and write paths of the original big-endian buffer, copied loop for loop,
+// benchmarked next to the current ones so every run compares against them on the same machine
+#[allow(clippy::needless_range_loop)]
+mod baseline {
+    pub struct Buffer {
+        pub data: Vec<u8>,
+        pub length: usize,
+        pub position: usize,
+    }
+
+    impl Buffer {
+        pub fn new() -> Buffer {
+            Buffer { data: Vec::with_capacity(muscleman::Buffer::Buffer::DEFAULT_CAPACITY), length: 0, position: 0 }
+        }
+
+        pub fn wrap(data: Vec<u8>) -> Buffer {
+            Buffer { length: data.len(), data, position: 0 }
+        }
+
+        pub fn read_u32(&mut self) -> Option<u32> {
+            if self.position + 4 <= self.length {
+                let mut bytes = [0; 4];
+                for i in 0..4 {
+                    bytes[i] = self.data[self.position + i];
+                }
+                self.position += 4;
+                Some(u32::from_be_bytes(bytes))
+            } else {
+                None
+            }
+        }
+
+        pub fn read_string(&mut self) -> Option<String> {
+            let mut string = String::new();
+            while self.position < self.length {
+                let byte = self.data[self.position];
+                self.position += 1;
+                if byte == 0 {
+                    break;
+                }
+                string.push(byte as char);
+            }
+            Some(string)
+        }
+
+        pub fn write_u32(&mut self, value: u32) {
+            let bytes = value.to_be_bytes();
+            for byte in &bytes {
+                self.data.push(*byte);
+            }
+            self.length += 4;
+        }
+
+        pub fn write_string(&mut self, string: &str) {
+            for byte in string.bytes() {
+                self.data.push(byte);
+            }
+            self.data.push(0);
+            self.length += string.len() + 1;
+        }
+    }
+}
+
+// Encodes a packet with a mix of field types
+fn write_packet(buffer: &mut Buffer, i: u64) {
+    buffer.write_u8(0x2A);
+    buffer.write_u16(i as u16);
+    buffer.write_i32(-(i as i32));
+    buffer.write_u64(i);
+    buffer.write_f32(i as f32);
+    buffer.write_varint(i as i64);
+    buffer.write_string_with_length("player");
+}
+
+fn read_packet(buffer: &mut Buffer) {
+    black_box(buffer.read_u8());
+    black_box(buffer.read_u16());
+    black_box(buffer.read_i32());
+    black_box(buffer.read_u64());
+    black_box(buffer.read_f32());
+    black_box(buffer.read_varint());
+    black_box(buffer.read_string_with_length());
+}
+
+fn packets(c: &mut Criterion) {
+    let mut encoded = Buffer::new();
+    for i in 0..COUNT as u64 {
+        write_packet(&mut encoded, i);
+    }
+    let data = encoded.get_data();
+
+    let mut group = c.benchmark_group("packets");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.bench_function("encode", |b| {
+        b.iter(|| {
+            let mut buffer = Buffer::new();
+            for i in 0..COUNT as u64 {
+                write_packet(&mut buffer, black_box(i));
+            }
+            buffer
+        })
+    });
+    group.bench_function("decode", |b| {
+        b.iter(|| {
+            let mut buffer = Buffer::wrap(data.clone());
+            for _ in 0..COUNT {
+                read_packet(&mut buffer);
+            }
+        })
+    });
+    group.finish();
+}
+
+fn integers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("integers");
+    group.throughput(Throughput::Bytes((COUNT * 4) as u64));
+    for byte_order in [ByteOrder::BigEndian, ByteOrder::LittleEndian] {
+        let mut encoded = Buffer::new();
+        encoded.set_byte_order(byte_order);
+        for i in 0..COUNT as u32 {
+            encoded.write_u32(i);
+        }
+        let data = encoded.get_data();
+
+        group.bench_function(format!("write_u32 {:?}", byte_order), |b| {
+            b.iter(|| {
+                let mut buffer = Buffer::new();
+                buffer.set_byte_order(byte_order);
+                for i in 0..COUNT as u32 {
+                    buffer.write_u32(black_box(i));
+                }
+                buffer
+            })
+        });
+        // The original buffer was always big-endian
+        if byte_order == ByteOrder::BigEndian {
+            group.bench_function("write_u32 BigEndian baseline", |b| {
+                b.iter(|| {
+                    let mut writer = baseline::Buffer::new();
+                    for i in 0..COUNT as u32 {
+                        writer.write_u32(black_box(i));
+                    }
+                    writer.data
+                })
+            });
+            // The original buffer could only read a vector it owned, so the copy is made outside the timing
+            group.bench_function("read_u32 BigEndian baseline", |b| {
+                b.iter_batched(|| baseline::Buffer::wrap(data.clone()), |mut reader| {
+                    for _ in 0..COUNT {
+                        black_box(reader.read_u32());
+                    }
+                }, BatchSize::SmallInput)
+            });
+        }
+        group.bench_function(format!("read_u32 {:?}", byte_order), |b| {
+            b.iter(|| {
+                let mut buffer = Buffer::wrap(&data[..]);
+                buffer.set_byte_order(byte_order);
+                for _ in 0..COUNT {
+                    black_box(buffer.read_u32());
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn strings(c: &mut Criterion) {
+    let text = "The quick brown fox jumps over the lazy dog";
+    let mut encoded = Buffer::new();
+    for _ in 0..COUNT {
+        encoded.write_string(text);
+    }
+    let data = encoded.get_data();
+
+    let mut group = c.benchmark_group("strings");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.bench_function("write_string", |b| {
+        b.iter(|| {
+            let mut buffer = Buffer::new();
+            for _ in 0..COUNT {
+                buffer.write_string(black_box(text));
+            }
+            buffer
+        })
+    });
+    group.bench_function("write_string baseline", |b| {
+        b.iter(|| {
+            let mut writer = baseline::Buffer::new();
+            for _ in 0..COUNT {
+                writer.write_string(black_box(text));
+            }
+            writer.data
+        })
+    });
+    group.bench_function("read_string baseline", |b| {
+        b.iter_batched(|| baseline::Buffer::wrap(data.clone()), |mut reader| {
+            for _ in 0..COUNT {
+                black_box(reader.read_string());
+            }
+        }, BatchSize::SmallInput)
+    });
+    group.bench_function("read_string", |b| {
+        b.iter(|| {
+            let mut buffer = Buffer::wrap(&data[..]);
+            for _ in 0..COUNT {
+                black_box(buffer.read_string());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, packets, integers, strings);
+criterion_main!(benches);
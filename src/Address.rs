@@ -0,0 +1,115 @@
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use crate::Buffer::Buffer;
+use crate::ByteOrder::ByteOrder;
+use crate::Storage::{Storage, StorageMut};
+
+// Addresses are always in network byte order, only ports follow the order they are given
+
+// The family byte written before a socket address
+const FAMILY_IPV4: u8 = 4;
+const FAMILY_IPV6: u8 = 6;
+
+impl<S: Storage> Buffer<S> {
+
+    //#region Address reading methods
+
+    /// Reads the 4 bytes of an IPv4 address.
+    pub fn read_ipv4(&mut self) -> Option<Ipv4Addr> {
+        self.take().map(Ipv4Addr::from)
+    }
+
+    /// Reads the 16 bytes of an IPv6 address.
+    pub fn read_ipv6(&mut self) -> Option<Ipv6Addr> {
+        self.take().map(Ipv6Addr::from)
+    }
+
+    /// Reads an IPv4 address followed by a port in the given byte order.
+    pub fn read_socket_addr_v4(&mut self, port_order: ByteOrder) -> Option<SocketAddrV4> {
+        self.atomic(|buffer| {
+            let ip = buffer.read_ipv4()?;
+            let port = buffer.with_byte_order(port_order, |buffer| buffer.read_u16())?;
+            Some(SocketAddrV4::new(ip, port))
+        })
+    }
+
+    /// Reads an IPv6 address followed by a port in the given byte order. The flow info and scope ID are 0.
+    pub fn read_socket_addr_v6(&mut self, port_order: ByteOrder) -> Option<SocketAddrV6> {
+        self.atomic(|buffer| {
+            let ip = buffer.read_ipv6()?;
+            let port = buffer.with_byte_order(port_order, |buffer| buffer.read_u16())?;
+            Some(SocketAddrV6::new(ip, port, 0, 0))
+        })
+    }
+
+    /// Reads a socket address prefixed with its family, 4 or 6, then its address and a port in the given byte order.
+    /// Fails on any other family.
+    pub fn read_socket_addr(&mut self, port_order: ByteOrder) -> Option<SocketAddr> {
+        self.atomic(|buffer| {
+            match buffer.peek_byte()? {
+                FAMILY_IPV4 => {
+                    buffer.position += 1;
+                    buffer.read_socket_addr_v4(port_order).map(SocketAddr::V4)
+                }
+                FAMILY_IPV6 => {
+                    buffer.position += 1;
+                    buffer.read_socket_addr_v6(port_order).map(SocketAddr::V6)
+                }
+                _ => None,
+            }
+        })
+    }
+
+    //#endregion Address reading methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region Address writing methods
+
+    /// Writes the 4 bytes of an IPv4 address.
+    pub fn write_ipv4(&mut self, ip: Ipv4Addr) -> Option<()> {
+        self.write_bytes(&ip.octets())
+    }
+
+    /// Writes the 16 bytes of an IPv6 address.
+    pub fn write_ipv6(&mut self, ip: Ipv6Addr) -> Option<()> {
+        self.write_bytes(&ip.octets())
+    }
+
+    /// Writes an IPv4 address followed by a port in the given byte order.
+    pub fn write_socket_addr_v4(&mut self, addr: SocketAddrV4, port_order: ByteOrder) -> Option<()> {
+        if !self.has_space(6) {
+            return None;
+        }
+        self.write_ipv4(*addr.ip())?;
+        self.with_byte_order(port_order, |buffer| buffer.write_u16(addr.port()))
+    }
+
+    /// Writes an IPv6 address followed by a port in the given byte order. The flow info and scope ID are dropped.
+    pub fn write_socket_addr_v6(&mut self, addr: SocketAddrV6, port_order: ByteOrder) -> Option<()> {
+        if !self.has_space(18) {
+            return None;
+        }
+        self.write_ipv6(*addr.ip())?;
+        self.with_byte_order(port_order, |buffer| buffer.write_u16(addr.port()))
+    }
+
+    /// Writes a socket address prefixed with its family, 4 or 6, then its address and a port in the given byte order.
+    pub fn write_socket_addr(&mut self, addr: SocketAddr, port_order: ByteOrder) -> Option<()> {
+        let (family, length) = match addr.ip() {
+            IpAddr::V4(_) => (FAMILY_IPV4, 7),
+            IpAddr::V6(_) => (FAMILY_IPV6, 19),
+        };
+        if !self.has_space(length) {
+            return None;
+        }
+        self.write_u8(family)?;
+        match addr {
+            SocketAddr::V4(addr) => self.write_socket_addr_v4(addr, port_order),
+            SocketAddr::V6(addr) => self.write_socket_addr_v6(addr, port_order),
+        }
+    }
+
+    //#endregion Address writing methods
+}
@@ -0,0 +1,136 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Buffer::Buffer;
+use crate::Storage::Storage;
+
+/// A Base64 alphabet and padding style, as described by RFC 4648.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Base64Variant {
+    /// The standard alphabet, ending with `+` and `/`, padded with `=`
+    Standard,
+
+    /// The standard alphabet without padding
+    StandardNoPad,
+
+    /// The URL and filename safe alphabet, ending with `-` and `_`, padded with `=`
+    UrlSafe,
+
+    /// The URL and filename safe alphabet without padding
+    UrlSafeNoPad,
+}
+
+impl Base64Variant {
+    // Gets whether the variant uses the URL and filename safe alphabet
+    fn is_url_safe(self) -> bool {
+        matches!(self, Base64Variant::UrlSafe | Base64Variant::UrlSafeNoPad)
+    }
+
+    // Gets whether encoding pads the last group to 4 characters
+    fn is_padded(self) -> bool {
+        matches!(self, Base64Variant::Standard | Base64Variant::UrlSafe)
+    }
+}
+
+// The characters of the standard alphabet
+const STANDARD_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// The characters of the URL and filename safe alphabet
+const URL_SAFE_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+// Gets the 6-bit value of a character of the given alphabet
+fn base64_value(character: u8, url_safe: bool) -> Option<u32> {
+    let value = match character {
+        b'A'..=b'Z' => character - b'A',
+        b'a'..=b'z' => character - b'a' + 26,
+        b'0'..=b'9' => character - b'0' + 52,
+        b'+' if !url_safe => 62,
+        b'/' if !url_safe => 63,
+        b'-' if url_safe => 62,
+        b'_' if url_safe => 63,
+        _ => return None,
+    };
+    Some(value as u32)
+}
+
+impl Buffer {
+
+    //#region Base64 constructors
+
+    /// Creates a buffer from Base64 text in the given variant's alphabet.
+    /// Whitespace, including newlines, is skipped, and padding is optional whatever the variant.
+    /// Fails on characters outside the alphabet, misplaced padding, or a truncated last group.
+    ///
+    /// ```rust
+    /// use muscleman::Base64::Base64Variant;
+    /// use muscleman::Buffer::Buffer;
+    ///
+    /// let buffer = Buffer::from_base64("3q2+7w==", Base64Variant::Standard).unwrap();
+    /// assert_eq!(buffer.as_slice(), [0xDE, 0xAD, 0xBE, 0xEF]);
+    /// assert_eq!(buffer.to_base64(Base64Variant::UrlSafeNoPad), "3q2-7w");
+    /// ```
+    pub fn from_base64(text: &str, variant: Base64Variant) -> Option<Buffer> {
+        let url_safe = variant.is_url_safe();
+        let mut bytes = Vec::with_capacity(text.len() / 4 * 3 + 2);
+        let (mut accumulator, mut bits, mut characters, mut padding) = (0u32, 0, 0, 0);
+        for character in text.bytes() {
+            match character {
+                character if character.is_ascii_whitespace() => {}
+                b'=' => padding += 1,
+                // Nothing but whitespace and padding can follow padding
+                _ if padding > 0 => return None,
+                character => {
+                    accumulator = accumulator << 6 | base64_value(character, url_safe)?;
+                    bits += 6;
+                    characters += 1;
+                    if bits >= 8 {
+                        bits -= 8;
+                        bytes.push((accumulator >> bits) as u8);
+                        accumulator &= (1 << bits) - 1;
+                    }
+                }
+            }
+        }
+        // A single character of a group can't hold a whole byte
+        if characters % 4 == 1 {
+            return None;
+        }
+        if padding > 0 && (characters % 4 == 0 || padding != 4 - characters % 4) {
+            return None;
+        }
+        // The bits left over past the last byte must be zero
+        if accumulator != 0 {
+            return None;
+        }
+        Some(Buffer::wrap(bytes))
+    }
+
+    //#endregion Base64 constructors
+}
+
+impl<S: Storage> Buffer<S> {
+
+    //#region Base64 methods
+
+    /// Gets the buffer's contents as Base64 text in the given variant, without line breaks.
+    pub fn to_base64(&self, variant: Base64Variant) -> String {
+        let alphabet = if variant.is_url_safe() { URL_SAFE_ALPHABET } else { STANDARD_ALPHABET };
+        let bytes = self.as_slice();
+        let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+        for chunk in bytes.chunks(3) {
+            let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| group | (*byte as u32) << (16 - 8 * index));
+            // n bytes take n + 1 characters
+            for index in 0..=chunk.len() {
+                text.push(alphabet[(group >> (18 - 6 * index)) as usize & 0x3F] as char);
+            }
+            if variant.is_padded() {
+                for _ in chunk.len()..3 {
+                    text.push('=');
+                }
+            }
+        }
+        text
+    }
+
+    //#endregion Base64 methods
+}
@@ -0,0 +1,503 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::fmt;
+
+use crate::Buffer::Buffer;
+use crate::ByteOrder::ByteOrder;
+use crate::DecodeLimits::DecodeLimits;
+use crate::Storage::{Storage, StorageMut};
+
+// BSON is always little-endian, whatever the buffer's byte order
+
+// The error messages shared by several element types
+const UNEXPECTED_END: &str = "unexpected end of document";
+const LIMIT_EXCEEDED: &str = "value exceeds the decode limits";
+
+/// The value of a BSON element, one variant per element type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BsonValue {
+    /// 64-bit floating-point number, type `0x01`
+    Double(f64),
+
+    /// UTF-8 string, type `0x02`
+    String(String),
+
+    /// Embedded document, type `0x03`
+    Document(Vec<(String, BsonValue)>),
+
+    /// Array, type `0x04`, stored as a document keyed `"0"`, `"1"`, ...
+    Array(Vec<BsonValue>),
+
+    /// Binary data with its subtype, type `0x05`
+    Binary(u8, Vec<u8>),
+
+    /// Deprecated undefined value, type `0x06`
+    Undefined,
+
+    /// 12-byte ObjectId, type `0x07`
+    ObjectId([u8; 12]),
+
+    /// Boolean, type `0x08`
+    Bool(bool),
+
+    /// UTC datetime in milliseconds since the Unix epoch, type `0x09`
+    DateTime(i64),
+
+    /// Null, type `0x0A`
+    Null,
+
+    /// Regular expression and its options, type `0x0B`
+    Regex { pattern: String, options: String },
+
+    /// Deprecated pointer to a document in another collection, type `0x0C`
+    DbPointer { namespace: String, id: [u8; 12] },
+
+    /// JavaScript code, type `0x0D`
+    JavaScript(String),
+
+    /// Deprecated symbol, type `0x0E`
+    Symbol(String),
+
+    /// JavaScript code with the document its variables are scoped to, type `0x0F`
+    JavaScriptWithScope { code: String, scope: Vec<(String, BsonValue)> },
+
+    /// 32-bit integer, type `0x10`
+    Int32(i32),
+
+    /// Internal MongoDB timestamp, type `0x11`
+    Timestamp { time: u32, increment: u32 },
+
+    /// 64-bit integer, type `0x12`
+    Int64(i64),
+
+    /// IEEE 754-2008 128-bit decimal in its little-endian bytes, type `0x13`
+    Decimal128([u8; 16]),
+
+    /// Compares lower than every other value, type `0xFF`
+    MinKey,
+
+    /// Compares higher than every other value, type `0x7F`
+    MaxKey,
+}
+
+impl BsonValue {
+    /// Gets the type byte written before the element's key.
+    pub fn element_type(&self) -> u8 {
+        match self {
+            BsonValue::Double(_) => 0x01,
+            BsonValue::String(_) => 0x02,
+            BsonValue::Document(_) => 0x03,
+            BsonValue::Array(_) => 0x04,
+            BsonValue::Binary(_, _) => 0x05,
+            BsonValue::Undefined => 0x06,
+            BsonValue::ObjectId(_) => 0x07,
+            BsonValue::Bool(_) => 0x08,
+            BsonValue::DateTime(_) => 0x09,
+            BsonValue::Null => 0x0A,
+            BsonValue::Regex { .. } => 0x0B,
+            BsonValue::DbPointer { .. } => 0x0C,
+            BsonValue::JavaScript(_) => 0x0D,
+            BsonValue::Symbol(_) => 0x0E,
+            BsonValue::JavaScriptWithScope { .. } => 0x0F,
+            BsonValue::Int32(_) => 0x10,
+            BsonValue::Timestamp { .. } => 0x11,
+            BsonValue::Int64(_) => 0x12,
+            BsonValue::Decimal128(_) => 0x13,
+            BsonValue::MinKey => 0xFF,
+            BsonValue::MaxKey => 0x7F,
+        }
+    }
+}
+
+/// An error raised while reading a malformed BSON document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BsonError {
+    // The error message
+    message: &'static str,
+
+    // The offset in the buffer decoding stopped at
+    offset: usize,
+}
+
+impl fmt::Display for BsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BsonError {}
+
+impl BsonError {
+    /// Gets the error message
+    pub fn message(&self) -> &str {
+        self.message
+    }
+
+    /// Gets the offset in the buffer decoding stopped at
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+/// A BSON document read by `Buffer::read_bson_document`, whose length and terminator have been validated.
+/// Its elements are only decoded while iterating over it.
+///
+/// ```rust
+/// use muscleman::Buffer::Buffer;
+/// use muscleman::Bson::BsonValue;
+///
+/// let mut buffer = Buffer::new();
+/// buffer.write_bson_document(&[("answer".to_string(), BsonValue::Int32(42))]);
+///
+/// let document = buffer.read_bson_document().unwrap();
+/// for element in document {
+///     let (key, value) = element.unwrap();
+///     assert_eq!((key.as_str(), value), ("answer", BsonValue::Int32(42)));
+/// }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct BsonDocument<'a> {
+    // The bytes of the whole document, including its length and terminator
+    bytes: &'a [u8],
+
+    // The offset of the document in the buffer it was read from
+    offset: usize,
+
+    // The limits and nesting depth inherited from the buffer
+    decode_limits: DecodeLimits,
+    depth: usize,
+
+    // The buffer's allocation count, which every iteration adds to
+    allocated: &'a Cell<usize>,
+}
+
+impl<'a> BsonDocument<'a> {
+    /// Gets the bytes of the whole document, including its length and terminator.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Gets an iterator decoding the elements of the document in order.
+    /// What it decodes counts against the `max_total_allocation` limit of the buffer the document was read from.
+    pub fn iter(&self) -> BsonElements<'a> {
+        let mut buffer = Buffer::wrap(self.bytes);
+        buffer.byte_order = ByteOrder::LittleEndian;
+        buffer.decode_limits = self.decode_limits;
+        buffer.depth = self.depth;
+        buffer.allocated = self.allocated.get();
+        buffer.position = 4;
+        BsonElements { buffer, offset: self.offset, count: 0, done: false, allocated: self.allocated }
+    }
+
+    /// Decodes every element of the document, failing on the first malformed one.
+    pub fn to_vec(&self) -> Result<Vec<(String, BsonValue)>, BsonError> {
+        self.iter().collect()
+    }
+}
+
+impl<'a> IntoIterator for BsonDocument<'a> {
+    type Item = Result<(String, BsonValue), BsonError>;
+    type IntoIter = BsonElements<'a>;
+
+    fn into_iter(self) -> BsonElements<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over the elements of a `BsonDocument`, yielding each key and value.
+/// Stops after the first error.
+#[derive(Debug)]
+pub struct BsonElements<'a> {
+    // A buffer over the document's bytes, positioned at the next element
+    buffer: Buffer<&'a [u8]>,
+
+    // The offset of the document in the buffer it was read from
+    offset: usize,
+
+    // The number of elements decoded so far
+    count: usize,
+
+    // Whether the terminator or an error has been reached
+    done: bool,
+
+    // The allocation count of the buffer the document was read from
+    allocated: &'a Cell<usize>,
+}
+
+impl Iterator for BsonElements<'_> {
+    type Item = Result<(String, BsonValue), BsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        // Starts from the count left by other iterators over the same document
+        self.buffer.allocated = self.allocated.get();
+        let element = match self.buffer.read_bson_element(self.count) {
+            Ok(Some(element)) => Ok(element),
+            // The terminator has to be the last byte of the document
+            Ok(None) if self.buffer.remaining() == 0 => {
+                self.done = true;
+                return None;
+            }
+            Ok(None) => Err("document length does not match its elements"),
+            Err(message) => Err(message),
+        };
+        self.allocated.set(self.buffer.allocated);
+        self.count += 1;
+        self.done = element.is_err();
+        Some(element.map_err(|message| BsonError { message, offset: self.offset + self.buffer.position }))
+    }
+}
+
+impl<S: Storage> Buffer<S> {
+
+    //#region BSON reading methods
+
+    /// Reads a BSON document, validating its length and terminator, and returns it for iterating over its elements.
+    /// The document counts as one level of nesting. Fails without moving the position.
+    pub fn read_bson_document(&mut self) -> Result<BsonDocument<'_>, BsonError> {
+        let offset = self.position;
+        let error = |message| BsonError { message, offset };
+        let length = self.peek_bytes(4).ok_or(error(UNEXPECTED_END))?;
+        let length = i32::from_le_bytes(length.try_into().map_err(|_| error(UNEXPECTED_END))?);
+        let length = usize::try_from(length).ok().filter(|length| *length >= 5).ok_or(error("invalid document length"))?;
+        if length > self.remaining() {
+            return Err(error(UNEXPECTED_END));
+        }
+        if self.unread()[length - 1] != 0 {
+            return Err(error("document is missing its terminator"));
+        }
+        if self.depth >= self.decode_limits.max_nesting_depth {
+            return Err(error(LIMIT_EXCEEDED));
+        }
+        let (decode_limits, depth) = (self.decode_limits, self.depth + 1);
+        self.position += length;
+        // The bytes and the allocation count are borrowed separately, so the document can charge its reads to the buffer
+        let bytes = &self.data.written(self.length)[offset..offset + length];
+        let allocated = Cell::from_mut(&mut self.allocated);
+        Ok(BsonDocument { bytes, offset, decode_limits, depth, allocated })
+    }
+
+    // The methods below expect the buffer to be little-endian
+
+    // Reads an element's type, key and value, or nothing at the terminator of its document
+    fn read_bson_element(&mut self, count: usize) -> Result<Option<(String, BsonValue)>, &'static str> {
+        let element_type = self.read_u8().ok_or(UNEXPECTED_END)?;
+        if element_type == 0 {
+            return Ok(None);
+        }
+        self.check_collection_count(count + 1).ok_or(LIMIT_EXCEEDED)?;
+        self.allocate(core::mem::size_of::<(String, BsonValue)>()).ok_or(LIMIT_EXCEEDED)?;
+        let key = self.read_bson_cstring()?;
+        let value = self.read_bson_value(element_type)?;
+        Ok(Some((key, value)))
+    }
+
+    // Reads the value of an element of the given type
+    fn read_bson_value(&mut self, element_type: u8) -> Result<BsonValue, &'static str> {
+        let value = match element_type {
+            0x01 => BsonValue::Double(self.read_f64().ok_or(UNEXPECTED_END)?),
+            0x02 => BsonValue::String(self.read_bson_string()?),
+            0x03 => BsonValue::Document(self.read_bson_fields()?),
+            // Arrays are documents whose keys are only the indexes of their items
+            0x04 => BsonValue::Array(self.read_bson_fields()?.into_iter().map(|(_, value)| value).collect()),
+            0x05 => {
+                let length = self.read_i32().ok_or(UNEXPECTED_END)?;
+                let length = usize::try_from(length).map_err(|_| "invalid binary length")?;
+                let subtype = self.read_u8().ok_or(UNEXPECTED_END)?;
+                BsonValue::Binary(subtype, self.read_bson_bytes(length, self.decode_limits.max_bytes_length)?)
+            }
+            0x06 => BsonValue::Undefined,
+            0x07 => BsonValue::ObjectId(self.take().ok_or(UNEXPECTED_END)?),
+            0x08 => match self.read_u8().ok_or(UNEXPECTED_END)? {
+                0 => BsonValue::Bool(false),
+                1 => BsonValue::Bool(true),
+                _ => return Err("invalid boolean"),
+            },
+            0x09 => BsonValue::DateTime(self.read_i64().ok_or(UNEXPECTED_END)?),
+            0x0A => BsonValue::Null,
+            0x0B => BsonValue::Regex { pattern: self.read_bson_cstring()?, options: self.read_bson_cstring()? },
+            0x0C => BsonValue::DbPointer { namespace: self.read_bson_string()?, id: self.take().ok_or(UNEXPECTED_END)? },
+            0x0D => BsonValue::JavaScript(self.read_bson_string()?),
+            0x0E => BsonValue::Symbol(self.read_bson_string()?),
+            0x0F => {
+                // The length covers itself, the code and the scope
+                let start = self.position;
+                let length = self.read_i32().ok_or(UNEXPECTED_END)?;
+                let code = self.read_bson_string()?;
+                let scope = self.read_bson_fields()?;
+                if usize::try_from(length).ok() != Some(self.position - start) {
+                    return Err("code with scope length does not match its contents");
+                }
+                BsonValue::JavaScriptWithScope { code, scope }
+            }
+            0x10 => BsonValue::Int32(self.read_i32().ok_or(UNEXPECTED_END)?),
+            0x11 => {
+                let increment = self.read_u32().ok_or(UNEXPECTED_END)?;
+                BsonValue::Timestamp { time: self.read_u32().ok_or(UNEXPECTED_END)?, increment }
+            }
+            0x12 => BsonValue::Int64(self.read_i64().ok_or(UNEXPECTED_END)?),
+            0x13 => BsonValue::Decimal128(self.take().ok_or(UNEXPECTED_END)?),
+            0xFF => BsonValue::MinKey,
+            0x7F => BsonValue::MaxKey,
+            _ => return Err("unknown element type"),
+        };
+        Ok(value)
+    }
+
+    // Reads an embedded document, checking its elements end exactly at its length
+    fn read_bson_fields(&mut self) -> Result<Vec<(String, BsonValue)>, &'static str> {
+        let start = self.position;
+        let length = self.read_i32().ok_or(UNEXPECTED_END)?;
+        let length = usize::try_from(length).ok().filter(|length| *length >= 5).ok_or("invalid document length")?;
+        if length - 4 > self.remaining() {
+            return Err(UNEXPECTED_END);
+        }
+        self.enter_nested().ok_or(LIMIT_EXCEEDED)?;
+        let mut fields = Vec::new();
+        let read = loop {
+            match self.read_bson_element(fields.len()) {
+                Ok(Some(field)) => fields.push(field),
+                Ok(None) => break Ok(()),
+                Err(message) => break Err(message),
+            }
+        };
+        self.exit_nested();
+        read?;
+        if self.position - start != length {
+            return Err("document length does not match its elements");
+        }
+        Ok(fields)
+    }
+
+    // Reads a string prefixed with its length, which counts its NUL terminator
+    fn read_bson_string(&mut self) -> Result<String, &'static str> {
+        let length = self.read_i32().ok_or(UNEXPECTED_END)?;
+        let length = usize::try_from(length).ok().filter(|length| *length >= 1).ok_or("invalid string length")?;
+        let bytes = self.read_bson_bytes(length - 1, self.decode_limits.max_string_length)?;
+        if self.read_u8() != Some(0) {
+            return Err("string is missing its terminator");
+        }
+        String::from_utf8(bytes).map_err(|_| "string is not valid UTF-8")
+    }
+
+    // Reads a NUL-terminated string, used for keys and regular expressions
+    fn read_bson_cstring(&mut self) -> Result<String, &'static str> {
+        let length = self.unread().iter().position(|byte| *byte == 0).ok_or("string is missing its terminator")?;
+        let bytes = self.read_bson_bytes(length, self.decode_limits.max_string_length)?;
+        self.position += 1;
+        String::from_utf8(bytes).map_err(|_| "string is not valid UTF-8")
+    }
+
+    // Reads n bytes, telling a read past the end apart from a limit
+    fn read_bson_bytes(&mut self, n: usize, max_length: usize) -> Result<Vec<u8>, &'static str> {
+        if n > self.remaining() {
+            return Err(UNEXPECTED_END);
+        }
+        self.read_limited_bytes(n, max_length).ok_or(LIMIT_EXCEEDED)
+    }
+
+    //#endregion BSON reading methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region BSON writing methods
+
+    /// Writes a BSON document with the given keys and values.
+    /// Fails if a key or regular expression contains a NUL byte, or a length doesn't fit in an `i32`, and then writes nothing.
+    pub fn write_bson_document(&mut self, fields: &[(String, BsonValue)]) -> Option<()> {
+        let mut document = Buffer::new();
+        document.byte_order = ByteOrder::LittleEndian;
+        document.write_bson_fields(fields)?;
+        self.write_bytes(document.as_slice())
+    }
+
+    // The methods below expect the buffer to be a little-endian temporary, since they can fail halfway
+
+    // Writes a document whose elements are written by `build`
+    // The elements are written in a separate buffer, since the document's length has to be written first.
+    fn write_bson_document_with(&mut self, build: impl FnOnce(&mut Buffer) -> Option<()>) -> Option<()> {
+        let mut elements = Buffer::new();
+        elements.byte_order = ByteOrder::LittleEndian;
+        build(&mut elements)?;
+        self.write_i32(i32::try_from(elements.len().checked_add(5)?).ok()?)?;
+        self.write_bytes(elements.as_slice())?;
+        self.write_u8(0)
+    }
+
+    // Writes a document with the given keys and values
+    fn write_bson_fields(&mut self, fields: &[(String, BsonValue)]) -> Option<()> {
+        self.write_bson_document_with(|elements| {
+            fields.iter().try_for_each(|(key, value)| elements.write_bson_element(key, value))
+        })
+    }
+
+    // Writes an element's type, key and value
+    fn write_bson_element(&mut self, key: &str, value: &BsonValue) -> Option<()> {
+        self.write_u8(value.element_type())?;
+        self.write_bson_cstring(key)?;
+        match value {
+            BsonValue::Double(value) => self.write_f64(*value),
+            BsonValue::String(string) | BsonValue::JavaScript(string) | BsonValue::Symbol(string) => {
+                self.write_bson_string(string)
+            }
+            BsonValue::Document(fields) => self.write_bson_fields(fields),
+            BsonValue::Array(items) => self.write_bson_document_with(|elements| {
+                items.iter().enumerate().try_for_each(|(index, item)| elements.write_bson_element(&index.to_string(), item))
+            }),
+            BsonValue::Binary(subtype, bytes) => {
+                self.write_i32(i32::try_from(bytes.len()).ok()?)?;
+                self.write_u8(*subtype)?;
+                self.write_bytes(bytes)
+            }
+            BsonValue::Undefined | BsonValue::Null | BsonValue::MinKey | BsonValue::MaxKey => Some(()),
+            BsonValue::ObjectId(id) => self.write_bytes(id),
+            BsonValue::Bool(value) => self.write_u8(*value as u8),
+            BsonValue::DateTime(value) | BsonValue::Int64(value) => self.write_i64(*value),
+            BsonValue::Regex { pattern, options } => {
+                self.write_bson_cstring(pattern)?;
+                self.write_bson_cstring(options)
+            }
+            BsonValue::DbPointer { namespace, id } => {
+                self.write_bson_string(namespace)?;
+                self.write_bytes(id)
+            }
+            BsonValue::JavaScriptWithScope { code, scope } => {
+                let mut contents = Buffer::new();
+                contents.byte_order = ByteOrder::LittleEndian;
+                contents.write_bson_string(code)?;
+                contents.write_bson_fields(scope)?;
+                self.write_i32(i32::try_from(contents.len().checked_add(4)?).ok()?)?;
+                self.write_bytes(contents.as_slice())
+            }
+            BsonValue::Int32(value) => self.write_i32(*value),
+            BsonValue::Timestamp { time, increment } => {
+                self.write_u32(*increment)?;
+                self.write_u32(*time)
+            }
+            BsonValue::Decimal128(bytes) => self.write_bytes(bytes),
+        }
+    }
+
+    // Writes a string prefixed with its length, which counts its NUL terminator
+    fn write_bson_string(&mut self, string: &str) -> Option<()> {
+        self.write_i32(i32::try_from(string.len().checked_add(1)?).ok()?)?;
+        self.write_bytes(string.as_bytes())?;
+        self.write_u8(0)
+    }
+
+    // Writes a NUL-terminated string, failing if it contains a NUL byte
+    fn write_bson_cstring(&mut self, string: &str) -> Option<()> {
+        if string.as_bytes().contains(&0) {
+            return None;
+        }
+        self.write_bytes(string.as_bytes())?;
+        self.write_u8(0)
+    }
+
+    //#endregion BSON writing methods
+}
@@ -1,30 +1,50 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
 use crate::ByteOrder::ByteOrder;
+use crate::DecodeLimits::DecodeLimits;
+use crate::Storage::{Storage, StorageMut};
 
+/// A buffer of bytes, read from the front and written at the end.
+///
+/// The bytes live in a storage `S`, which is a growable `Vec<u8>` by default.
+/// Borrowed `&[u8]` storage can only be read, while `&mut [u8]` and `[u8; N]` storage has a fixed size.
 #[allow(dead_code)]
-pub struct Buffer {
+pub struct Buffer<S: Storage = Vec<u8>> {
     // The buffer's data
-    data: Vec<u8>,
-
-    // The buffer's length
-    length: usize,
+    pub(crate) data: S,
 
-    // The buffer's capacity
-    capacity: usize,
+    // What the storage needs to know how many of its bytes are written, nothing for `Vec` storage
+    pub(crate) length: S::Length,
 
     // The buffer's position
-    position: usize,
+    pub(crate) position: usize,
 
     // The buffer's mark
-    mark: Option<usize>,
+    pub(crate) mark: Option<usize>,
 
     // The buffer's byte order
-    byte_order: ByteOrder,
+    pub(crate) byte_order: ByteOrder,
 
     // The buffer's string encoding
-    string_encoding: &'static str,
+    pub(crate) string_encoding: &'static str,
 
     // The buffer's string terminator
-    string_terminator: &'static str
+    pub(crate) string_terminator: &'static str,
+
+    // The limits enforced by length-driven reads
+    pub(crate) decode_limits: DecodeLimits,
+
+    // The number of bytes allocated by length-driven reads since the limits were set
+    pub(crate) allocated: usize,
+
+    // The current nesting depth of compound reads
+    pub(crate) depth: usize,
+
+    // The offset alignment is computed from, such as the start of the current record
+    pub(crate) alignment_base: usize
 }
 
 impl Buffer {
@@ -51,13 +71,16 @@ impl Buffer {
     pub fn new() -> Buffer {
         Buffer {
             data: Vec::with_capacity(Buffer::DEFAULT_CAPACITY),
-            length: 0,
-            capacity: Buffer::DEFAULT_CAPACITY,
+            length: (),
             position: 0,
             mark: None,
             byte_order: Buffer::DEFAULT_BYTE_ORDER,
             string_encoding: Buffer::DEFAULT_STRING_ENCODING,
-            string_terminator: Buffer::DEFAULT_STRING_TERMINATOR
+            string_terminator: Buffer::DEFAULT_STRING_TERMINATOR,
+            decode_limits: DecodeLimits::new(),
+            allocated: 0,
+            depth: 0,
+            alignment_base: 0
         }
     }
 
@@ -65,65 +88,259 @@ impl Buffer {
     pub fn new_with_all_args(capacity: usize, byte_order: ByteOrder, string_encoding: &'static str, string_terminator: &'static str) -> Buffer {
         Buffer {
             data: Vec::with_capacity(capacity),
-            length: 0,
-            capacity: capacity,
+            length: (),
             position: 0,
             mark: None,
-            byte_order: byte_order,
-            string_encoding: string_encoding,
-            string_terminator: string_terminator
+            byte_order,
+            string_encoding,
+            string_terminator,
+            decode_limits: DecodeLimits::new(),
+            allocated: 0,
+            depth: 0,
+            alignment_base: 0
         }
     }
 
     //#endregion
+}
+
+impl<S: Storage> Buffer<S> {
+
+    //#region Storage constructors
+
+    /// Creates a buffer over existing storage. All of the storage's bytes are readable.
+    pub fn wrap(storage: S) -> Buffer<S> {
+        let length = storage.full_length();
+        Buffer {
+            data: storage,
+            length,
+            position: 0,
+            mark: None,
+            byte_order: ByteOrder::BigEndian,
+            string_encoding: Buffer::DEFAULT_STRING_ENCODING,
+            string_terminator: Buffer::DEFAULT_STRING_TERMINATOR,
+            decode_limits: DecodeLimits::new(),
+            allocated: 0,
+            depth: 0,
+            alignment_base: 0
+        }
+    }
+
+    //#endregion Storage constructors
 
     //# region Properties
 
     /// Gets the buffer's data
     pub fn get_data(&self) -> Vec<u8> {
-        self.data.clone()
+        self.as_slice().to_vec()
+    }
+
+    /// Gets the bytes written to the buffer, without copying them
+    pub fn as_slice(&self) -> &[u8] {
+        self.data.written(self.length)
+    }
+
+    /// Gets the buffer's storage
+    pub fn get_storage(&self) -> &S {
+        &self.data
+    }
+
+    /// Consumes the buffer and returns its storage
+    pub fn into_storage(self) -> S {
+        self.data
+    }
+
+    /// Gets the buffer's byte order
+    pub fn get_byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+
+    /// Sets the byte order used by the multi-byte reading and writing methods
+    pub fn set_byte_order(&mut self, byte_order: ByteOrder) {
+        self.byte_order = byte_order;
+    }
+
+    /// Gets the limits enforced by length-driven reads
+    pub fn get_decode_limits(&self) -> DecodeLimits {
+        self.decode_limits
+    }
+
+    /// Sets the limits enforced by length-driven reads, and resets the allocation counter
+    pub fn set_decode_limits(&mut self, decode_limits: DecodeLimits) {
+        self.decode_limits = decode_limits;
+        self.allocated = 0;
+    }
+
+    /// Gets the offset alignment is computed from
+    pub fn get_alignment_base(&self) -> usize {
+        self.alignment_base
+    }
+
+    /// Sets the offset alignment is computed from, such as the start of a nested record
+    pub fn set_alignment_base(&mut self, alignment_base: usize) {
+        self.alignment_base = alignment_base;
+    }
+
+    /// Resets the number of bytes counted against `max_total_allocation`
+    /// Call this between messages when reusing a buffer for a stream of messages.
+    pub fn reset_allocation(&mut self) {
+        self.allocated = 0;
+    }
+
+    /// Gets the buffer's length
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Checks if the buffer is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the number of bytes left to read
+    pub fn remaining(&self) -> usize {
+        self.len() - self.position
     }
 
     //#endregion Properties
 
+    //#region Position methods
+
+    /// Gets the position of the next byte to read
+    pub fn get_position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves the read position. Fails if the position is past the end of the buffer.
+    pub fn set_position(&mut self, position: usize) -> Option<()> {
+        if position > self.len() {
+            return None;
+        }
+        self.position = position;
+        Some(())
+    }
+
+    /// Gets the next byte without consuming it.
+    pub fn peek_byte(&self) -> Option<u8> {
+        self.peek_bytes(1).map(|bytes| bytes[0])
+    }
+
+    /// Gets the next n bytes without consuming or copying them.
+    pub fn peek_bytes(&self, n: usize) -> Option<&[u8]> {
+        self.unread().get(..n)
+    }
+
+    //#endregion Position methods
+
     //#region Reading methods
 
+    // Every reading method fails without moving the position when the value isn't all there or is invalid
+
     //#region Basic reading methods
 
     /// Reads a byte from the buffer.
     pub fn read_byte(&mut self) -> Option<u8> {
-        if self.position < self.length {
-            let byte = self.data[self.position];
-            self.position += 1;
-            Some(byte)
-        } else {
-            None
-        }
+        let [byte] = self.take()?;
+        Some(byte)
     }
 
     /// Reads n bytes from the buffer.
+    /// Fails if n is larger than the `max_bytes_length` limit.
     pub fn read_bytes(&mut self, n: usize) -> Option<Vec<u8>> {
-        if self.position + n <= self.length {
-            let mut bytes = Vec::with_capacity(n);
-            for i in 0..n {
-                bytes.push(self.data[self.position + i]);
-            }
-            self.position += n;
-            Some(bytes)
-        } else {
-            None
+        self.read_limited_bytes(n, self.decode_limits.max_bytes_length)
+    }
+
+    // Reads n bytes after checking them against a length limit and the allocation limit
+    pub(crate) fn read_limited_bytes(&mut self, n: usize, max_length: usize) -> Option<Vec<u8>> {
+        if n > max_length || n > self.remaining() {
+            return None;
+        }
+        self.allocate(n)?;
+        self.take_slice(n).map(<[u8]>::to_vec)
+    }
+
+    // Consumes the next N bytes as an array, the single bounds check of every fixed-width read
+    pub(crate) fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        if self.remaining() < N {
+            return None;
+        }
+        let (bytes, _) = self.unread().split_at(N);
+        let bytes = bytes.try_into().ok()?;
+        self.position += N;
+        Some(bytes)
+    }
+
+    // Consumes the next n bytes without copying them
+    pub(crate) fn take_slice(&mut self, n: usize) -> Option<&[u8]> {
+        if self.remaining() < n {
+            return None;
+        }
+        let start = self.position;
+        self.position += n;
+        Some(&self.as_slice()[start..start + n])
+    }
+
+    // Runs a read or write in the given byte order, restoring the buffer's byte order afterwards
+    pub(crate) fn with_byte_order<T>(&mut self, byte_order: ByteOrder, f: impl FnOnce(&mut Self) -> T) -> T {
+        let previous = core::mem::replace(&mut self.byte_order, byte_order);
+        let value = f(self);
+        self.byte_order = previous;
+        value
+    }
+
+    // Runs a read that either succeeds or leaves the position, allocation count and depth as they were
+    pub(crate) fn atomic<T>(&mut self, read: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        let (position, allocated, depth) = (self.position, self.allocated, self.depth);
+        let value = read(self);
+        if value.is_none() {
+            self.position = position;
+            self.allocated = allocated;
+            self.depth = depth;
         }
+        value
+    }
+
+    // Gets the bytes that haven't been read yet
+    pub(crate) fn unread(&self) -> &[u8] {
+        &self.as_slice()[self.position..]
+    }
+
+    // Counts n bytes against the `max_total_allocation` limit
+    pub(crate) fn allocate(&mut self, n: usize) -> Option<()> {
+        let allocated = self.allocated.checked_add(n)?;
+        if allocated > self.decode_limits.max_total_allocation {
+            return None;
+        }
+        self.allocated = allocated;
+        Some(())
+    }
+
+    // Enters a nested compound value, failing past the `max_nesting_depth` limit
+    pub(crate) fn enter_nested(&mut self) -> Option<()> {
+        if self.depth >= self.decode_limits.max_nesting_depth {
+            return None;
+        }
+        self.depth += 1;
+        Some(())
+    }
+
+    // Leaves a nested compound value
+    pub(crate) fn exit_nested(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    // Checks the item count of a collection against the `max_collection_count` limit
+    pub(crate) fn check_collection_count(&self, count: usize) -> Option<()> {
+        if count > self.decode_limits.max_collection_count {
+            return None;
+        }
+        Some(())
     }
 
     /// Reads a boolean from the buffer.
     pub fn read_boolean(&mut self) -> Option<bool> {
-        if self.position < self.length {
-            let byte = self.data[self.position];
-            self.position += 1;
-            Some(byte != 0)
-        } else {
-            None
-        }
+        let [byte] = self.take()?;
+        Some(byte != 0)
     }
 
     //#endregion Basic reading methods
@@ -134,54 +351,34 @@ impl Buffer {
 
     /// Reads a signed 8-bit integer from the buffer.
     pub fn read_i8(&mut self) -> Option<i8> {
-        if self.position < self.length {
-            let byte = self.data[self.position];
-            self.position += 1;
-            Some(byte as i8)
-        } else {
-            None
-        }
+        let [byte] = self.take()?;
+        Some(byte as i8)
     }
 
     /// Reads a signed 16-bit integer from the buffer.
     pub fn read_i16(&mut self) -> Option<i16> {
-        if self.position + 2 <= self.length {
-            let mut bytes = [0; 2];
-            for i in 0..2 {
-                bytes[i] = self.data[self.position + i];
-            }
-            self.position += 2;
-            Some(i16::from_be_bytes(bytes))
-        } else {
-            None
+        let bytes = self.take()?;
+        match self.byte_order {
+            ByteOrder::BigEndian => Some(i16::from_be_bytes(bytes)),
+            ByteOrder::LittleEndian => Some(i16::from_le_bytes(bytes)),
         }
     }
 
     /// Reads a signed 32-bit integer from the buffer.
     pub fn read_i32(&mut self) -> Option<i32> {
-        if self.position + 4 <= self.length {
-            let mut bytes = [0; 4];
-            for i in 0..4 {
-                bytes[i] = self.data[self.position + i];
-            }
-            self.position += 4;
-            Some(i32::from_be_bytes(bytes))
-        } else {
-            None
+        let bytes = self.take()?;
+        match self.byte_order {
+            ByteOrder::BigEndian => Some(i32::from_be_bytes(bytes)),
+            ByteOrder::LittleEndian => Some(i32::from_le_bytes(bytes)),
         }
     }
 
     /// Reads a signed 64-bit integer from the buffer.
     pub fn read_i64(&mut self) -> Option<i64> {
-        if self.position + 8 <= self.length {
-            let mut bytes = [0; 8];
-            for i in 0..8 {
-                bytes[i] = self.data[self.position + i];
-            }
-            self.position += 8;
-            Some(i64::from_be_bytes(bytes))
-        } else {
-            None
+        let bytes = self.take()?;
+        match self.byte_order {
+            ByteOrder::BigEndian => Some(i64::from_be_bytes(bytes)),
+            ByteOrder::LittleEndian => Some(i64::from_le_bytes(bytes)),
         }
     }
 
@@ -191,54 +388,34 @@ impl Buffer {
 
     /// Reads an unsigned 8-bit integer from the buffer.
     pub fn read_u8(&mut self) -> Option<u8> {
-        if self.position < self.length {
-            let byte = self.data[self.position];
-            self.position += 1;
-            Some(byte)
-        } else {
-            None
-        }
+        let [byte] = self.take()?;
+        Some(byte)
     }
 
     /// Reads an unsigned 16-bit integer from the buffer.
     pub fn read_u16(&mut self) -> Option<u16> {
-        if self.position + 2 <= self.length {
-            let mut bytes = [0; 2];
-            for i in 0..2 {
-                bytes[i] = self.data[self.position + i];
-            }
-            self.position += 2;
-            Some(u16::from_be_bytes(bytes))
-        } else {
-            None
+        let bytes = self.take()?;
+        match self.byte_order {
+            ByteOrder::BigEndian => Some(u16::from_be_bytes(bytes)),
+            ByteOrder::LittleEndian => Some(u16::from_le_bytes(bytes)),
         }
     }
 
     /// Reads an unsigned 32-bit integer from the buffer.
     pub fn read_u32(&mut self) -> Option<u32> {
-        if self.position + 4 <= self.length {
-            let mut bytes = [0; 4];
-            for i in 0..4 {
-                bytes[i] = self.data[self.position + i];
-            }
-            self.position += 4;
-            Some(u32::from_be_bytes(bytes))
-        } else {
-            None
+        let bytes = self.take()?;
+        match self.byte_order {
+            ByteOrder::BigEndian => Some(u32::from_be_bytes(bytes)),
+            ByteOrder::LittleEndian => Some(u32::from_le_bytes(bytes)),
         }
     }
 
     /// Reads an unsigned 64-bit integer from the buffer.
     pub fn read_u64(&mut self) -> Option<u64> {
-        if self.position + 8 <= self.length {
-            let mut bytes = [0; 8];
-            for i in 0..8 {
-                bytes[i] = self.data[self.position + i];
-            }
-            self.position += 8;
-            Some(u64::from_be_bytes(bytes))
-        } else {
-            None
+        let bytes = self.take()?;
+        match self.byte_order {
+            ByteOrder::BigEndian => Some(u64::from_be_bytes(bytes)),
+            ByteOrder::LittleEndian => Some(u64::from_le_bytes(bytes)),
         }
     }
 
@@ -247,24 +424,18 @@ impl Buffer {
     //#region VarInt reading methods
 
     /// Reads a VarInt from the buffer.
+    /// Fails if the VarInt is longer than the 10 bytes needed for a 64-bit value.
     pub fn read_varint(&mut self) -> Option<i64> {
         let mut result: i64 = 0;
-        let mut shift: u8 = 0;
-        let mut byte: u8;
-        loop {
-            if self.position < self.length {
-                byte = self.data[self.position];
-                self.position += 1;
-            } else {
-                return None;
-            }
-            result |= ((byte & 0x7F) as i64) << shift;
+        // Decoded in place, so the position only moves once the whole VarInt is there
+        for (index, byte) in self.unread().iter().take(10).enumerate() {
+            result |= ((byte & 0x7F) as i64) << (7 * index);
             if byte & 0x80 == 0 {
-                break;
+                self.position += index + 1;
+                return Some(result);
             }
-            shift += 7;
         }
-        Some(result)
+        None
     }
 
     //#endregion VarInt reading methods
@@ -275,29 +446,19 @@ impl Buffer {
 
     /// Reads a 32-bit floating-point number from the buffer.
     pub fn read_f32(&mut self) -> Option<f32> {
-        if self.position + 4 <= self.length {
-            let mut bytes = [0; 4];
-            for i in 0..4 {
-                bytes[i] = self.data[self.position + i];
-            }
-            self.position += 4;
-            Some(f32::from_be_bytes(bytes))
-        } else {
-            None
+        let bytes = self.take()?;
+        match self.byte_order {
+            ByteOrder::BigEndian => Some(f32::from_be_bytes(bytes)),
+            ByteOrder::LittleEndian => Some(f32::from_le_bytes(bytes)),
         }
     }
 
     /// Reads a 64-bit floating-point number from the buffer.
     pub fn read_f64(&mut self) -> Option<f64> {
-        if self.position + 8 <= self.length {
-            let mut bytes = [0; 8];
-            for i in 0..8 {
-                bytes[i] = self.data[self.position + i];
-            }
-            self.position += 8;
-            Some(f64::from_be_bytes(bytes))
-        } else {
-            None
+        let bytes = self.take()?;
+        match self.byte_order {
+            ByteOrder::BigEndian => Some(f64::from_be_bytes(bytes)),
+            ByteOrder::LittleEndian => Some(f64::from_le_bytes(bytes)),
         }
     }
 
@@ -306,52 +467,112 @@ impl Buffer {
     //#region String reading methods
 
     /// Reads a string from the buffer.
-    /// Reads until the first null byte.
+    /// Reads until the first null byte, and skips it.
+    /// Fails without moving the position if there is no null byte yet, or the string is longer than the `max_string_length` limit.
     pub fn read_string(&mut self) -> Option<String> {
-        let mut string = String::new();
-        while self.position < self.length {
-            let byte = self.data[self.position];
-            self.position += 1;
-            if byte == 0 {
-                break;
-            }
-            string.push(byte as char);
+        let end = self.unread().iter().position(|byte| *byte == 0)?;
+        if end > self.decode_limits.max_string_length {
+            return None;
         }
+        self.allocate(end)?;
+        let string = bytes_to_string(self.take_slice(end)?);
+        self.position += 1;
         Some(string)
     }
 
     /// Reads a string from the buffer.
     /// Reads the length of the string from the buffer. Then reads that many bytes.
+    /// Fails if the length is larger than the `max_string_length` limit.
     pub fn read_string_with_length(&mut self) -> Option<String> {
-        let length = self.read_u32()?;
-        let mut string = String::new();
-        for _ in 0..length {
-            let byte = self.read_u8()?;
-            string.push(byte as char);
-        }
-        Some(string)
+        self.atomic(|buffer| {
+            let length = usize::try_from(buffer.read_u32()?).ok()?;
+            if length > buffer.decode_limits.max_string_length || length > buffer.remaining() {
+                return None;
+            }
+            buffer.allocate(length)?;
+            Some(bytes_to_string(buffer.take_slice(length)?))
+        })
     }
 
     //#endregion String reading methods
 
     //#endregion Reading methods
 
+    //#region Transaction methods
+
+    /// Runs a group of reads and writes that either all happen or none do.
+    /// If the closure returns `None`, the position moves back to where it was, and with writable storage everything written since is dropped.
+    /// Read-only buffers, such as sub-readers and submessages, can use transactions to roll back reads.
+    /// The start of the transaction is kept as the buffer's mark, and an outer transaction's mark is restored afterwards.
+    ///
+    /// ```rust
+    /// use muscleman::Buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::wrap(vec![0x00, 0x01]);
+    /// let header = buffer.transaction(|buffer| {
+    ///     buffer.write_u8(0xFF)?;
+    ///     Some((buffer.read_u16()?, buffer.read_u16()?))
+    /// });
+    /// assert_eq!(header, None);
+    /// assert_eq!((buffer.get_position(), buffer.len()), (0, 2));
+    /// ```
+    pub fn transaction<T>(&mut self, f: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        let outer = self.mark.replace(self.position);
+        let (length, allocated, depth) = (self.len(), self.allocated, self.depth);
+        let value = f(self);
+        if value.is_none() {
+            if let Some(mark) = self.mark {
+                self.position = mark;
+            }
+            self.data.truncate(&mut self.length, length);
+            self.allocated = allocated;
+            self.depth = depth;
+        }
+        self.mark = outer;
+        value
+    }
+
+    //#endregion Transaction methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region Storage constructors
+
+    /// Creates an empty buffer over existing storage, to write into it.
+    /// A `Vec<u8>` storage is cleared, fixed-size storage is overwritten from the start.
+    pub fn wrap_empty(mut storage: S) -> Buffer<S> {
+        let length = storage.clear();
+        let mut buffer = Buffer::wrap(storage);
+        buffer.length = length;
+        buffer
+    }
+
+    //#endregion Storage constructors
+
     //#region Writing methods
 
+    // Every writing method fails without writing anything when the storage is full
+
     //#region Basic writing methods
 
     /// Writes a byte to the buffer.
-    pub fn write_byte(&mut self, byte: u8) {
-        self.data.push(byte);
-        self.length += 1;
+    pub fn write_byte(&mut self, byte: u8) -> Option<()> {
+        self.write_bytes(&[byte])
     }
 
     /// Writes a byte array to the buffer.
-    pub fn write_bytes(&mut self, bytes: &[u8]) {
-        for byte in bytes {
-            self.data.push(*byte);
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Option<()> {
+        if !self.has_space(bytes.len()) {
+            return None;
         }
-        self.length += bytes.len();
+        self.data.append(&mut self.length, bytes);
+        Some(())
+    }
+
+    // Checks if n more bytes fit in the storage
+    pub(crate) fn has_space(&self, n: usize) -> bool {
+        n <= self.data.max_len() - self.len()
     }
 
     //#endregion Basic writing methods
@@ -361,36 +582,35 @@ impl Buffer {
     //# region Signed integer writing methods
 
     /// Writes a signed 8-bit integer to the buffer.
-    pub fn write_i8(&mut self, value: i8) {
-        self.data.push(value as u8);
-        self.length += 1;
+    pub fn write_i8(&mut self, value: i8) -> Option<()> {
+        self.write_bytes(&[value as u8])
     }
 
     /// Writes a signed 16-bit integer to the buffer.
-    pub fn write_i16(&mut self, value: i16) {
-        let bytes = value.to_be_bytes();
-        for byte in &bytes {
-            self.data.push(*byte);
-        }
-        self.length += 2;
+    pub fn write_i16(&mut self, value: i16) -> Option<()> {
+        let bytes = match self.byte_order {
+            ByteOrder::BigEndian => value.to_be_bytes(),
+            ByteOrder::LittleEndian => value.to_le_bytes(),
+        };
+        self.write_bytes(&bytes)
     }
 
     /// Writes a signed 32-bit integer to the buffer.
-    pub fn write_i32(&mut self, value: i32) {
-        let bytes = value.to_be_bytes();
-        for byte in &bytes {
-            self.data.push(*byte);
-        }
-        self.length += 4;
+    pub fn write_i32(&mut self, value: i32) -> Option<()> {
+        let bytes = match self.byte_order {
+            ByteOrder::BigEndian => value.to_be_bytes(),
+            ByteOrder::LittleEndian => value.to_le_bytes(),
+        };
+        self.write_bytes(&bytes)
     }
 
     /// Writes a signed 64-bit integer to the buffer.
-    pub fn write_i64(&mut self, value: i64) {
-        let bytes = value.to_be_bytes();
-        for byte in &bytes {
-            self.data.push(*byte);
-        }
-        self.length += 8;
+    pub fn write_i64(&mut self, value: i64) -> Option<()> {
+        let bytes = match self.byte_order {
+            ByteOrder::BigEndian => value.to_be_bytes(),
+            ByteOrder::LittleEndian => value.to_le_bytes(),
+        };
+        self.write_bytes(&bytes)
     }
 
     //# endregion Signed integer writing methods
@@ -398,36 +618,35 @@ impl Buffer {
     //# region Unsigned integer writing methods
 
     /// Writes an unsigned 8-bit integer to the buffer.
-    pub fn write_u8(&mut self, value: u8) {
-        self.data.push(value);
-        self.length += 1;
+    pub fn write_u8(&mut self, value: u8) -> Option<()> {
+        self.write_bytes(&[value])
     }
 
     /// Writes an unsigned 16-bit integer to the buffer.
-    pub fn write_u16(&mut self, value: u16) {
-        let bytes = value.to_be_bytes();
-        for byte in &bytes {
-            self.data.push(*byte);
-        }
-        self.length += 2;
+    pub fn write_u16(&mut self, value: u16) -> Option<()> {
+        let bytes = match self.byte_order {
+            ByteOrder::BigEndian => value.to_be_bytes(),
+            ByteOrder::LittleEndian => value.to_le_bytes(),
+        };
+        self.write_bytes(&bytes)
     }
 
     /// Writes an unsigned 32-bit integer to the buffer.
-    pub fn write_u32(&mut self, value: u32) {
-        let bytes = value.to_be_bytes();
-        for byte in &bytes {
-            self.data.push(*byte);
-        }
-        self.length += 4;
+    pub fn write_u32(&mut self, value: u32) -> Option<()> {
+        let bytes = match self.byte_order {
+            ByteOrder::BigEndian => value.to_be_bytes(),
+            ByteOrder::LittleEndian => value.to_le_bytes(),
+        };
+        self.write_bytes(&bytes)
     }
 
     /// Writes an unsigned 64-bit integer to the buffer.
-    pub fn write_u64(&mut self, value: u64) {
-        let bytes = value.to_be_bytes();
-        for byte in &bytes {
-            self.data.push(*byte);
-        }
-        self.length += 8;
+    pub fn write_u64(&mut self, value: u64) -> Option<()> {
+        let bytes = match self.byte_order {
+            ByteOrder::BigEndian => value.to_be_bytes(),
+            ByteOrder::LittleEndian => value.to_le_bytes(),
+        };
+        self.write_bytes(&bytes)
     }
 
     //# endregion Unsigned integer writing methods
@@ -435,20 +654,24 @@ impl Buffer {
     //#region VarInt writing methods
 
     /// Writes a VarInt to the buffer.
-    pub fn write_varint(&mut self, value: i64) {
-        let mut value = value;
+    /// Negative values are written as their 64-bit two's complement, which takes 10 bytes.
+    pub fn write_varint(&mut self, value: i64) -> Option<()> {
+        let mut bytes = [0; 10];
+        let mut length = 0;
+        let mut value = value as u64;
         loop {
             let mut temp = (value & 0x7F) as u8;
             value >>= 7;
             if value != 0 {
                 temp |= 0x80;
             }
-            self.data.push(temp);
-            self.length += 1;
+            bytes[length] = temp;
+            length += 1;
             if value == 0 {
                 break;
             }
         }
+        self.write_bytes(&bytes[..length])
     }
 
     //#endregion VarInt writing methods
@@ -458,21 +681,21 @@ impl Buffer {
     //#region Floating-point writing methods
 
     /// Writes a 32-bit floating-point number to the buffer.
-    pub fn write_f32(&mut self, value: f32) {
-        let bytes = value.to_be_bytes();
-        for byte in &bytes {
-            self.data.push(*byte);
-        }
-        self.length += 4;
+    pub fn write_f32(&mut self, value: f32) -> Option<()> {
+        let bytes = match self.byte_order {
+            ByteOrder::BigEndian => value.to_be_bytes(),
+            ByteOrder::LittleEndian => value.to_le_bytes(),
+        };
+        self.write_bytes(&bytes)
     }
 
     /// Writes a 64-bit floating-point number to the buffer.
-    pub fn write_f64(&mut self, value: f64) {
-        let bytes = value.to_be_bytes();
-        for byte in &bytes {
-            self.data.push(*byte);
-        }
-        self.length += 8;
+    pub fn write_f64(&mut self, value: f64) -> Option<()> {
+        let bytes = match self.byte_order {
+            ByteOrder::BigEndian => value.to_be_bytes(),
+            ByteOrder::LittleEndian => value.to_le_bytes(),
+        };
+        self.write_bytes(&bytes)
     }
 
     //#endregion Floating-point writing methods
@@ -481,22 +704,23 @@ impl Buffer {
 
     /// Writes a string to the buffer.
     /// Writes as a null-terminated string.
-    pub fn write_string(&mut self, string: &str) {
-        for byte in string.bytes() {
-            self.data.push(byte);
+    pub fn write_string(&mut self, string: &str) -> Option<()> {
+        if !self.has_space(string.len().checked_add(1)?) {
+            return None;
         }
-        self.data.push(0);
-        self.length += string.len() + 1;
+        self.write_bytes(string.as_bytes())?;
+        self.write_byte(0)
     }
 
     /// Writes a string to the buffer.
     /// Writes the length of the string as a 32-bit unsigned integer, then writes the string.
-    pub fn write_string_with_length(&mut self, string: &str) {
-        self.write_u32(string.len() as u32);
-        for byte in string.bytes() {
-            self.data.push(byte);
+    pub fn write_string_with_length(&mut self, string: &str) -> Option<()> {
+        let length = u32::try_from(string.len()).ok()?;
+        if !self.has_space(string.len().checked_add(4)?) {
+            return None;
         }
-        self.length += string.len();
+        self.write_u32(length)?;
+        self.write_bytes(string.as_bytes())
     }
 
     //#endregion String writing methods
@@ -504,3 +728,177 @@ impl Buffer {
     //#endregion Writing methods
 }
 
+// Maps every byte to the char with the same value, copying ASCII in one go
+fn bytes_to_string(bytes: &[u8]) -> String {
+    match core::str::from_utf8(bytes) {
+        Ok(string) if bytes.is_ascii() => String::from(string),
+        _ => bytes.iter().map(|byte| *byte as char).collect(),
+    }
+}
+
+impl Default for Buffer {
+    fn default() -> Self {
+        Buffer::new()
+    }
+}
+
+impl<S: Storage + Clone> Clone for Buffer<S> {
+    fn clone(&self) -> Self {
+        Buffer {
+            data: self.data.clone(),
+            length: self.length,
+            position: self.position,
+            mark: self.mark,
+            byte_order: self.byte_order,
+            string_encoding: self.string_encoding,
+            string_terminator: self.string_terminator,
+            decode_limits: self.decode_limits,
+            allocated: self.allocated,
+            depth: self.depth,
+            alignment_base: self.alignment_base
+        }
+    }
+}
+
+// Buffers compare and hash by the bytes written to them, whatever their storage, position or settings
+
+impl<S: Storage, T: Storage> PartialEq<Buffer<T>> for Buffer<S> {
+    fn eq(&self, other: &Buffer<T>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<S: Storage> Eq for Buffer<S> {}
+
+impl<S: Storage> Hash for Buffer<S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+
+impl<S: Storage> AsRef<[u8]> for Buffer<S> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl From<Vec<u8>> for Buffer {
+    fn from(bytes: Vec<u8>) -> Self {
+        Buffer::wrap(bytes)
+    }
+}
+
+impl From<&[u8]> for Buffer {
+    fn from(bytes: &[u8]) -> Self {
+        Buffer::wrap(bytes.to_vec())
+    }
+}
+
+impl From<&str> for Buffer {
+    fn from(string: &str) -> Self {
+        Buffer::wrap(string.as_bytes().to_vec())
+    }
+}
+
+impl From<Buffer> for Vec<u8> {
+    fn from(buffer: Buffer) -> Self {
+        // A vector only ever holds the written bytes
+        buffer.into_storage()
+    }
+}
+
+impl Extend<u8> for Buffer {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, bytes: I) {
+        self.data.extend(bytes);
+    }
+}
+
+impl<'a> Extend<&'a u8> for Buffer {
+    fn extend<I: IntoIterator<Item = &'a u8>>(&mut self, bytes: I) {
+        self.extend(bytes.into_iter().copied());
+    }
+}
+
+impl FromIterator<u8> for Buffer {
+    fn from_iter<I: IntoIterator<Item = u8>>(bytes: I) -> Self {
+        Buffer::wrap(bytes.into_iter().collect())
+    }
+}
+
+// Iterates over every byte written to the buffer, from the start whatever the position
+impl IntoIterator for Buffer {
+    type Item = u8;
+    type IntoIter = alloc::vec::IntoIter<u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_storage().into_iter()
+    }
+}
+
+// Iterates over every byte written to the buffer, from the start whatever the position
+impl<'a, S: Storage> IntoIterator for &'a Buffer<S> {
+    type Item = &'a u8;
+    type IntoIter = core::slice::Iter<'a, u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+impl<S: Storage> fmt::Debug for Buffer<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Buffer")
+            .field("length", &self.len())
+            .field("position", &self.position)
+            .field("mark", &self.mark)
+            .field("byte_order", &self.byte_order)
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: Storage> std::io::Read for Buffer<S> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = buf.len().min(self.remaining());
+        buf[..n].copy_from_slice(self.take_slice(n).unwrap_or_default());
+        Ok(n)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: Storage> std::io::Seek for Buffer<S> {
+    fn seek(&mut self, from: std::io::SeekFrom) -> std::io::Result<u64> {
+        let position = match from {
+            std::io::SeekFrom::Start(offset) => usize::try_from(offset).ok(),
+            std::io::SeekFrom::End(offset) => offset_position(self.len(), offset),
+            std::io::SeekFrom::Current(offset) => offset_position(self.position, offset),
+        };
+        match position {
+            Some(position) if position <= self.len() => {
+                self.position = position;
+                Ok(position as u64)
+            }
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "seek outside of the buffer")),
+        }
+    }
+}
+
+// Applies a signed seek offset to a position
+#[cfg(feature = "std")]
+fn offset_position(base: usize, offset: i64) -> Option<usize> {
+    let offset = isize::try_from(offset).ok()?;
+    base.checked_add_signed(offset)
+}
+
+#[cfg(feature = "std")]
+impl<S: StorageMut> std::io::Write for Buffer<S> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = buf.len().min(self.data.max_len() - self.len());
+        self.write_bytes(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
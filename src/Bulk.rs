@@ -0,0 +1,177 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::Buffer::Buffer;
+use crate::ByteOrder::ByteOrder;
+use crate::Storage::{Storage, StorageMut};
+
+// A primitive that can be copied to and from bytes in native order
+trait Primitive: Copy + Default {
+    const SIZE: usize;
+
+    fn from_ne_chunk(chunk: &[u8]) -> Self;
+    fn to_ne_chunk(self, chunk: &mut [u8]);
+    fn swap_bytes(self) -> Self;
+}
+
+macro_rules! impl_primitive {
+    ($($ty:ty),*) => {$(
+        impl Primitive for $ty {
+            const SIZE: usize = core::mem::size_of::<$ty>();
+
+            fn from_ne_chunk(chunk: &[u8]) -> Self {
+                <$ty>::from_ne_bytes(chunk.try_into().unwrap())
+            }
+
+            fn to_ne_chunk(self, chunk: &mut [u8]) {
+                chunk.copy_from_slice(&self.to_ne_bytes());
+            }
+
+            fn swap_bytes(self) -> Self {
+                <$ty>::swap_bytes(self)
+            }
+        }
+    )*};
+}
+
+impl_primitive!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+macro_rules! impl_float_primitive {
+    ($($ty:ty),*) => {$(
+        impl Primitive for $ty {
+            const SIZE: usize = core::mem::size_of::<$ty>();
+
+            fn from_ne_chunk(chunk: &[u8]) -> Self {
+                <$ty>::from_ne_bytes(chunk.try_into().unwrap())
+            }
+
+            fn to_ne_chunk(self, chunk: &mut [u8]) {
+                chunk.copy_from_slice(&self.to_ne_bytes());
+            }
+
+            fn swap_bytes(self) -> Self {
+                <$ty>::from_bits(self.to_bits().swap_bytes())
+            }
+        }
+    )*};
+}
+
+impl_float_primitive!(f32, f64);
+
+// The size of the stack chunk values are encoded into before being written
+const WRITE_CHUNK_SIZE: usize = 256;
+
+macro_rules! bulk_reading_methods {
+    ($($ty:ty => $read_into:ident, $read_vec:ident;)*) => {$(
+        #[doc = concat!("Reads enough `", stringify!($ty), "` values from the buffer to fill `out`.")]
+        #[doc = "Fails without reading anything if there aren't enough bytes left."]
+        pub fn $read_into(&mut self, out: &mut [$ty]) -> Option<()> {
+            self.read_array_into(out)
+        }
+
+        #[doc = concat!("Reads `count` `", stringify!($ty), "` values from the buffer.")]
+        #[doc = "Fails if `count` is larger than the `max_collection_count` limit."]
+        pub fn $read_vec(&mut self, count: usize) -> Option<Vec<$ty>> {
+            self.read_array_vec(count)
+        }
+    )*};
+}
+
+macro_rules! bulk_writing_methods {
+    ($($ty:ty => $write_slice:ident;)*) => {$(
+        #[doc = concat!("Writes a slice of `", stringify!($ty), "` values to the buffer.")]
+        pub fn $write_slice(&mut self, values: &[$ty]) -> Option<()> {
+            self.write_array(values)
+        }
+    )*};
+}
+
+impl<S: Storage> Buffer<S> {
+
+    //#region Bulk reading methods
+
+    bulk_reading_methods! {
+        u8 => read_u8_into, read_u8_vec;
+        u16 => read_u16_into, read_u16_vec;
+        u32 => read_u32_into, read_u32_vec;
+        u64 => read_u64_into, read_u64_vec;
+        i8 => read_i8_into, read_i8_vec;
+        i16 => read_i16_into, read_i16_vec;
+        i32 => read_i32_into, read_i32_vec;
+        i64 => read_i64_into, read_i64_vec;
+        f32 => read_f32_into, read_f32_vec;
+        f64 => read_f64_into, read_f64_vec;
+    }
+
+    // Checks the bounds once, then copies every value, swapping bytes only if the order isn't native
+    fn read_array_into<T: Primitive>(&mut self, out: &mut [T]) -> Option<()> {
+        let n = out.len().checked_mul(T::SIZE)?;
+        let swap = self.byte_order != ByteOrder::NATIVE;
+        let bytes = self.peek_bytes(n)?;
+        let chunks = bytes.chunks_exact(T::SIZE);
+        if swap {
+            for (item, chunk) in out.iter_mut().zip(chunks) {
+                *item = T::from_ne_chunk(chunk).swap_bytes();
+            }
+        } else {
+            for (item, chunk) in out.iter_mut().zip(chunks) {
+                *item = T::from_ne_chunk(chunk);
+            }
+        }
+        self.position += n;
+        Some(())
+    }
+
+    // Checks the count against the limits before allocating anything
+    fn read_array_vec<T: Primitive>(&mut self, count: usize) -> Option<Vec<T>> {
+        let n = count.checked_mul(T::SIZE)?;
+        if n > self.remaining() {
+            return None;
+        }
+        self.check_collection_count(count)?;
+        self.allocate(n)?;
+        let mut out = vec![T::default(); count];
+        self.read_array_into(&mut out)?;
+        Some(out)
+    }
+
+    //#endregion Bulk reading methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region Bulk writing methods
+
+    bulk_writing_methods! {
+        u8 => write_u8_slice;
+        u16 => write_u16_slice;
+        u32 => write_u32_slice;
+        u64 => write_u64_slice;
+        i8 => write_i8_slice;
+        i16 => write_i16_slice;
+        i32 => write_i32_slice;
+        i64 => write_i64_slice;
+        f32 => write_f32_slice;
+        f64 => write_f64_slice;
+    }
+
+    // Checks the space once, then encodes the values through a stack chunk
+    fn write_array<T: Primitive>(&mut self, values: &[T]) -> Option<()> {
+        if !self.has_space(values.len().checked_mul(T::SIZE)?) {
+            return None;
+        }
+        let swap = self.byte_order != ByteOrder::NATIVE;
+        let mut chunk = [0; WRITE_CHUNK_SIZE];
+        for group in values.chunks(WRITE_CHUNK_SIZE / T::SIZE) {
+            let len = group.len() * T::SIZE;
+            for (value, out) in group.iter().zip(chunk.chunks_exact_mut(T::SIZE)) {
+                let value = if swap { value.swap_bytes() } else { *value };
+                value.to_ne_chunk(out);
+            }
+            self.write_bytes(&chunk[..len])?;
+        }
+        Some(())
+    }
+
+    //#endregion Bulk writing methods
+}
@@ -1,4 +1,11 @@
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum ByteOrder {
+    #[default]
     BigEndian = 0,
     LittleEndian = 1,
-}
\ No newline at end of file
+}
+
+impl ByteOrder {
+    /// The byte order of the platform the crate is compiled for
+    pub const NATIVE: ByteOrder = if cfg!(target_endian = "big") { ByteOrder::BigEndian } else { ByteOrder::LittleEndian };
+}
@@ -0,0 +1,468 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Buffer::Buffer;
+use crate::ByteOrder::ByteOrder;
+use crate::Numeric::{f16_to_f32, f32_to_f16};
+use crate::Storage::{Storage, StorageMut};
+use crate::Value::Value;
+
+// CBOR heads are always big-endian, whatever the buffer's byte order
+
+/// A single item pulled from a CBOR stream by `Buffer::read_cbor_event`.
+///
+/// Arrays, maps, tags and indefinite-length strings only announce their start.
+/// Their contents are the events that follow, and indefinite-length items end with `Break`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CborEvent {
+    /// Major type 0, an unsigned integer
+    UInt(u64),
+
+    /// Major type 1, the negative integer `-1 - n`
+    NegInt(u64),
+
+    /// Major type 2, a definite-length byte string, or a chunk of an indefinite-length one
+    Bytes(Vec<u8>),
+
+    /// Major type 3, a definite-length text string, or a chunk of an indefinite-length one
+    Text(String),
+
+    /// The start of an indefinite-length byte string, followed by `Bytes` chunks and a `Break`
+    BytesStart,
+
+    /// The start of an indefinite-length text string, followed by `Text` chunks and a `Break`
+    TextStart,
+
+    /// Major type 4, an array with the given number of items, or `None` for an indefinite-length array
+    Array(Option<u64>),
+
+    /// Major type 5, a map with the given number of pairs, or `None` for an indefinite-length map
+    Map(Option<u64>),
+
+    /// Major type 6, a tag applying to the item that follows
+    Tag(u64),
+
+    /// `false` or `true`
+    Bool(bool),
+
+    /// `null`
+    Null,
+
+    /// `undefined`
+    Undefined,
+
+    /// Any other simple value
+    Simple(u8),
+
+    /// A half, single or double precision float
+    Float(f64),
+
+    /// The end of an indefinite-length item
+    Break,
+}
+
+//#region Constants
+
+// The major types, in the high 3 bits of the initial byte
+const MAJOR_UINT: u8 = 0;
+const MAJOR_NEGINT: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const MAJOR_SIMPLE: u8 = 7;
+
+// The additional information marking an indefinite length, or a break in major type 7
+const INDEFINITE: u8 = 31;
+
+//#endregion
+
+impl<S: Storage> Buffer<S> {
+
+    //#region CBOR reading methods
+
+    /// Pulls the next event from a CBOR stream.
+    /// Definite-length strings are read whole, with the `max_bytes_length` and `max_string_length` limits.
+    /// Fails on malformed items, such as reserved additional information.
+    pub fn read_cbor_event(&mut self) -> Option<CborEvent> {
+        self.atomic(|buffer| {
+            let initial = buffer.peek_byte()?;
+            let (major, info) = (initial >> 5, initial & 0x1F);
+            if info == INDEFINITE {
+                let event = match major {
+                    MAJOR_BYTES => CborEvent::BytesStart,
+                    MAJOR_TEXT => CborEvent::TextStart,
+                    MAJOR_ARRAY => CborEvent::Array(None),
+                    MAJOR_MAP => CborEvent::Map(None),
+                    MAJOR_SIMPLE => CborEvent::Break,
+                    _ => return None,
+                };
+                buffer.read_u8()?;
+                return Some(event);
+            }
+
+            let argument = buffer.read_cbor_argument()?;
+            Some(match major {
+                MAJOR_UINT => CborEvent::UInt(argument),
+                MAJOR_NEGINT => CborEvent::NegInt(argument),
+                MAJOR_BYTES => CborEvent::Bytes(buffer.read_bytes(usize::try_from(argument).ok()?)?),
+                MAJOR_TEXT => {
+                    let bytes = buffer.read_limited_bytes(usize::try_from(argument).ok()?, buffer.decode_limits.max_string_length)?;
+                    CborEvent::Text(String::from_utf8(bytes).ok()?)
+                }
+                MAJOR_ARRAY => CborEvent::Array(Some(argument)),
+                MAJOR_MAP => CborEvent::Map(Some(argument)),
+                MAJOR_TAG => CborEvent::Tag(argument),
+                _ => match info {
+                    20 => CborEvent::Bool(false),
+                    21 => CborEvent::Bool(true),
+                    22 => CborEvent::Null,
+                    23 => CborEvent::Undefined,
+                    // Values below 32 must use the short form
+                    24 if argument < 32 => return None,
+                    25 => CborEvent::Float(f16_to_f32(argument as u16) as f64),
+                    26 => CborEvent::Float(f32::from_bits(argument as u32) as f64),
+                    27 => CborEvent::Float(f64::from_bits(argument)),
+                    _ => CborEvent::Simple(argument as u8),
+                },
+            })
+        })
+    }
+
+    /// Reads a whole CBOR data item as a value, joining indefinite-length strings.
+    /// Maps whose keys are all text become structs, other maps become `Value::Map`, and `undefined` becomes `Value::Null`.
+    /// Fails on negative integers below `i64::MIN`.
+    pub fn read_cbor_value(&mut self) -> Option<Value> {
+        self.atomic(|buffer| {
+            let event = buffer.read_cbor_event()?;
+            buffer.read_cbor_value_from(event)
+        })
+    }
+
+    // Reads the rest of the data item started by an event
+    fn read_cbor_value_from(&mut self, event: CborEvent) -> Option<Value> {
+        Some(match event {
+            CborEvent::UInt(value) => Value::UInt(value),
+            CborEvent::NegInt(value) => Value::Int(-1 - i64::try_from(value).ok()?),
+            CborEvent::Bytes(bytes) => Value::Bytes(bytes),
+            CborEvent::Text(text) => Value::String(text),
+            CborEvent::BytesStart => {
+                let mut bytes = Vec::new();
+                loop {
+                    match self.read_cbor_event()? {
+                        CborEvent::Bytes(chunk) => bytes.extend_from_slice(&chunk),
+                        CborEvent::Break => break,
+                        // Chunks must be definite-length strings of the same type
+                        _ => return None,
+                    }
+                    if bytes.len() > self.decode_limits.max_bytes_length {
+                        return None;
+                    }
+                }
+                Value::Bytes(bytes)
+            }
+            CborEvent::TextStart => {
+                let mut text = String::new();
+                loop {
+                    match self.read_cbor_event()? {
+                        CborEvent::Text(chunk) => text.push_str(&chunk),
+                        CborEvent::Break => break,
+                        _ => return None,
+                    }
+                    if text.len() > self.decode_limits.max_string_length {
+                        return None;
+                    }
+                }
+                Value::String(text)
+            }
+            CborEvent::Array(count) => Value::Array(self.read_cbor_items(count, 1)?),
+            CborEvent::Map(count) => Value::from_alternating(self.read_cbor_items(count, 2)?),
+            CborEvent::Tag(tag) => {
+                self.enter_nested()?;
+                let value = self.read_cbor_value();
+                self.exit_nested();
+                Value::Tag(tag, Box::new(value?))
+            }
+            CborEvent::Bool(value) => Value::Bool(value),
+            CborEvent::Null | CborEvent::Undefined => Value::Null,
+            CborEvent::Simple(value) => Value::Simple(value),
+            CborEvent::Float(value) => Value::Float(value),
+            CborEvent::Break => return None,
+        })
+    }
+
+    // Reads the items of an array, or the keys and values of a map, one level deeper
+    // An indefinite-length collection is read until its break.
+    fn read_cbor_items(&mut self, count: Option<u64>, items_per_entry: usize) -> Option<Vec<Value>> {
+        if let Some(count) = count {
+            self.check_collection_count(usize::try_from(count).ok()?)?;
+        }
+        self.enter_nested()?;
+        let items = self.read_cbor_entries(count, items_per_entry);
+        self.exit_nested();
+        items
+    }
+
+    fn read_cbor_entries(&mut self, count: Option<u64>, items_per_entry: usize) -> Option<Vec<Value>> {
+        let mut items = Vec::new();
+        let mut entries = 0;
+        while count.is_none_or(|count| entries < count) {
+            let event = self.read_cbor_event()?;
+            if count.is_none() && event == CborEvent::Break {
+                break;
+            }
+            entries += 1;
+            self.check_collection_count(usize::try_from(entries).ok()?)?;
+            self.allocate(core::mem::size_of::<Value>() * items_per_entry)?;
+            items.push(self.read_cbor_value_from(event)?);
+            for _ in 1..items_per_entry {
+                items.push(self.read_cbor_value()?);
+            }
+        }
+        Some(items)
+    }
+
+    // Reads the initial byte and returns its argument, the value following it for additional information 24 to 27
+    fn read_cbor_argument(&mut self) -> Option<u64> {
+        self.with_byte_order(ByteOrder::BigEndian, |buffer| {
+            let info = buffer.read_u8()? & 0x1F;
+            match info {
+                0..=23 => Some(info as u64),
+                24 => buffer.read_u8().map(u64::from),
+                25 => buffer.read_u16().map(u64::from),
+                26 => buffer.read_u32().map(u64::from),
+                27 => buffer.read_u64(),
+                // 28 to 30 are reserved, and 31 is handled by the caller
+                _ => None,
+            }
+        })
+    }
+
+    //#endregion CBOR reading methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region CBOR writing methods
+
+    /// Writes an unsigned integer, using the shortest head that holds it.
+    pub fn write_cbor_uint(&mut self, value: u64) -> Option<()> {
+        self.write_cbor_head(MAJOR_UINT, value, &[])
+    }
+
+    /// Writes a signed integer, as major type 0 or 1.
+    pub fn write_cbor_int(&mut self, value: i64) -> Option<()> {
+        if value >= 0 {
+            self.write_cbor_head(MAJOR_UINT, value as u64, &[])
+        } else {
+            // -1 - value, without overflowing for i64::MIN
+            self.write_cbor_head(MAJOR_NEGINT, !value as u64, &[])
+        }
+    }
+
+    /// Writes a definite-length byte string.
+    pub fn write_cbor_bytes(&mut self, bytes: &[u8]) -> Option<()> {
+        self.write_cbor_head(MAJOR_BYTES, bytes.len() as u64, bytes)
+    }
+
+    /// Writes a definite-length text string.
+    pub fn write_cbor_text(&mut self, text: &str) -> Option<()> {
+        self.write_cbor_head(MAJOR_TEXT, text.len() as u64, text.as_bytes())
+    }
+
+    /// Starts an indefinite-length byte string. Write its chunks with `write_cbor_bytes`, then `write_cbor_break`.
+    pub fn write_cbor_bytes_start(&mut self) -> Option<()> {
+        self.write_u8(MAJOR_BYTES << 5 | INDEFINITE)
+    }
+
+    /// Starts an indefinite-length text string. Write its chunks with `write_cbor_text`, then `write_cbor_break`.
+    pub fn write_cbor_text_start(&mut self) -> Option<()> {
+        self.write_u8(MAJOR_TEXT << 5 | INDEFINITE)
+    }
+
+    /// Writes the head of an array with `count` items, or an indefinite-length array if `count` is `None`.
+    pub fn write_cbor_array_start(&mut self, count: Option<u64>) -> Option<()> {
+        match count {
+            Some(count) => self.write_cbor_head(MAJOR_ARRAY, count, &[]),
+            None => self.write_u8(MAJOR_ARRAY << 5 | INDEFINITE),
+        }
+    }
+
+    /// Writes the head of a map with `count` pairs, or an indefinite-length map if `count` is `None`.
+    pub fn write_cbor_map_start(&mut self, count: Option<u64>) -> Option<()> {
+        match count {
+            Some(count) => self.write_cbor_head(MAJOR_MAP, count, &[]),
+            None => self.write_u8(MAJOR_MAP << 5 | INDEFINITE),
+        }
+    }
+
+    /// Ends an indefinite-length item.
+    pub fn write_cbor_break(&mut self) -> Option<()> {
+        self.write_u8(MAJOR_SIMPLE << 5 | INDEFINITE)
+    }
+
+    /// Writes a tag. The tagged item is written after it.
+    pub fn write_cbor_tag(&mut self, tag: u64) -> Option<()> {
+        self.write_cbor_head(MAJOR_TAG, tag, &[])
+    }
+
+    /// Writes `false` or `true`.
+    pub fn write_cbor_bool(&mut self, value: bool) -> Option<()> {
+        self.write_u8(MAJOR_SIMPLE << 5 | if value { 21 } else { 20 })
+    }
+
+    /// Writes `null`.
+    pub fn write_cbor_null(&mut self) -> Option<()> {
+        self.write_u8(MAJOR_SIMPLE << 5 | 22)
+    }
+
+    /// Writes `undefined`.
+    pub fn write_cbor_undefined(&mut self) -> Option<()> {
+        self.write_u8(MAJOR_SIMPLE << 5 | 23)
+    }
+
+    /// Writes a simple value. Fails for 24 to 31, which are reserved.
+    pub fn write_cbor_simple(&mut self, value: u8) -> Option<()> {
+        match value {
+            0..=23 => self.write_u8(MAJOR_SIMPLE << 5 | value),
+            24..=31 => None,
+            _ => self.write_bytes(&[MAJOR_SIMPLE << 5 | 24, value]),
+        }
+    }
+
+    /// Writes a half precision float, rounding to the nearest half.
+    pub fn write_cbor_f16(&mut self, value: f32) -> Option<()> {
+        self.write_cbor_head_sized(25, f32_to_f16(value) as u64)
+    }
+
+    /// Writes a single precision float.
+    pub fn write_cbor_f32(&mut self, value: f32) -> Option<()> {
+        self.write_cbor_head_sized(26, value.to_bits() as u64)
+    }
+
+    /// Writes a double precision float.
+    pub fn write_cbor_f64(&mut self, value: f64) -> Option<()> {
+        self.write_cbor_head_sized(27, value.to_bits())
+    }
+
+    /// Writes a float with the shortest precision that holds it exactly. NaN is written as a half.
+    pub fn write_cbor_float(&mut self, value: f64) -> Option<()> {
+        let single = value as f32;
+        if value.is_nan() {
+            self.write_cbor_head_sized(25, 0x7E00)
+        } else if single as f64 != value {
+            self.write_cbor_f64(value)
+        } else if f16_to_f32(f32_to_f16(single)) == single {
+            self.write_cbor_f16(single)
+        } else {
+            self.write_cbor_f32(single)
+        }
+    }
+
+    /// Writes any value as CBOR, with definite lengths, the shortest integer heads and the shortest exact floats.
+    /// Structs are written as maps with text keys, in their order.
+    /// Fails on `Value::Ext`, which CBOR has no equivalent for, leaving what was written so far.
+    pub fn write_cbor_value(&mut self, value: &Value) -> Option<()> {
+        self.write_cbor_value_with(value, false)
+    }
+
+    /// Writes any value as deterministically encoded CBOR (RFC 8949 section 4.2.1).
+    /// This is `write_cbor_value`, with the keys of every map sorted by their encoded bytes,
+    /// so equal values are always encoded to the same bytes.
+    pub fn write_cbor_value_canonical(&mut self, value: &Value) -> Option<()> {
+        self.write_cbor_value_with(value, true)
+    }
+
+    fn write_cbor_value_with(&mut self, value: &Value, canonical: bool) -> Option<()> {
+        match value {
+            Value::Null => self.write_cbor_null(),
+            Value::Bool(value) => self.write_cbor_bool(*value),
+            Value::Int(value) => self.write_cbor_int(*value),
+            Value::UInt(value) => self.write_cbor_uint(*value),
+            Value::Float(value) => self.write_cbor_float(*value),
+            Value::String(value) => self.write_cbor_text(value),
+            Value::Bytes(value) => self.write_cbor_bytes(value),
+            Value::Simple(value) => self.write_cbor_simple(*value),
+            Value::Ext(..) => None,
+            Value::Tag(tag, value) => {
+                self.write_cbor_tag(*tag)?;
+                self.write_cbor_value_with(value, canonical)
+            }
+            Value::Array(items) => {
+                self.write_cbor_array_start(Some(items.len() as u64))?;
+                items.iter().try_for_each(|item| self.write_cbor_value_with(item, canonical))
+            }
+            Value::Struct(fields) => {
+                let entries: Vec<(Value, &Value)> = fields.iter().map(|(name, value)| (Value::String(name.clone()), value)).collect();
+                self.write_cbor_map(&entries, canonical)
+            }
+            Value::Map(entries) => {
+                let entries: Vec<(Value, &Value)> = entries.iter().map(|(key, value)| (key.clone(), value)).collect();
+                self.write_cbor_map(&entries, canonical)
+            }
+        }
+    }
+
+    // Writes a map, sorting its keys by their encoded bytes in canonical mode
+    fn write_cbor_map(&mut self, entries: &[(Value, &Value)], canonical: bool) -> Option<()> {
+        self.write_cbor_map_start(Some(entries.len() as u64))?;
+        if !canonical {
+            return entries.iter().try_for_each(|(key, value)| {
+                self.write_cbor_value_with(key, canonical)?;
+                self.write_cbor_value_with(value, canonical)
+            });
+        }
+        let mut encoded = Vec::with_capacity(entries.len());
+        for (key, value) in entries {
+            let mut buffer = Buffer::new();
+            buffer.write_cbor_value_with(key, canonical)?;
+            encoded.push((buffer.get_data(), *value));
+        }
+        encoded.sort_by(|(a, _), (b, _)| a.cmp(b));
+        encoded.into_iter().try_for_each(|(key, value)| {
+            self.write_bytes(&key)?;
+            self.write_cbor_value_with(value, canonical)
+        })
+    }
+
+    // Writes a head with the shortest argument encoding, then the payload, only if both fit
+    fn write_cbor_head(&mut self, major: u8, argument: u64, payload: &[u8]) -> Option<()> {
+        let info = match argument {
+            0..=23 => argument as u8,
+            24..=0xFF => 24,
+            0x100..=0xFFFF => 25,
+            0x1_0000..=0xFFFF_FFFF => 26,
+            _ => 27,
+        };
+        let mut head = Buffer::wrap_empty([0; 9]);
+        write_cbor_initial(&mut head, major, info, argument)?;
+        if !self.has_space(head.len().checked_add(payload.len())?) {
+            return None;
+        }
+        self.write_bytes(head.as_slice())?;
+        self.write_bytes(payload)
+    }
+
+    // Writes a major type 7 head with an argument of the size given by the additional information
+    fn write_cbor_head_sized(&mut self, info: u8, argument: u64) -> Option<()> {
+        let mut head = Buffer::wrap_empty([0; 9]);
+        write_cbor_initial(&mut head, MAJOR_SIMPLE, info, argument)?;
+        self.write_bytes(head.as_slice())
+    }
+
+    //#endregion CBOR writing methods
+}
+
+// Writes an initial byte and the big-endian argument its additional information calls for
+fn write_cbor_initial(head: &mut Buffer<[u8; 9]>, major: u8, info: u8, argument: u64) -> Option<()> {
+    head.write_u8(major << 5 | info)?;
+    match info {
+        24 => head.write_u8(argument as u8),
+        25 => head.write_u16(argument as u16),
+        26 => head.write_u32(argument as u32),
+        27 => head.write_u64(argument),
+        _ => Some(()),
+    }
+}
@@ -0,0 +1,137 @@
+use alloc::vec::Vec;
+#[cfg(feature = "deflate")]
+use std::io::{Read, Write};
+
+use crate::Buffer::Buffer;
+use crate::Storage::{Storage, StorageMut};
+
+/// A compression codec that can be applied to a region of a buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Codec {
+    /// Raw deflate stream (RFC 1951)
+    #[cfg(feature = "deflate")]
+    Deflate,
+
+    /// Zlib wrapped deflate stream (RFC 1950)
+    #[cfg(feature = "deflate")]
+    Zlib,
+
+    /// Gzip wrapped deflate stream (RFC 1952)
+    #[cfg(feature = "deflate")]
+    Gzip,
+
+    /// LZ4 block, prefixed with its uncompressed size as a little-endian u32
+    #[cfg(feature = "lz4")]
+    Lz4,
+}
+
+impl Codec {
+    /// Compresses the given bytes.
+    pub fn compress(self, bytes: &[u8]) -> Option<Vec<u8>> {
+        match self {
+            #[cfg(feature = "deflate")]
+            Codec::Deflate => {
+                let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes).ok()?;
+                encoder.finish().ok()
+            }
+            #[cfg(feature = "deflate")]
+            Codec::Zlib => {
+                let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes).ok()?;
+                encoder.finish().ok()
+            }
+            #[cfg(feature = "deflate")]
+            Codec::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes).ok()?;
+                encoder.finish().ok()
+            }
+            #[cfg(feature = "lz4")]
+            Codec::Lz4 => Some(lz4_flex::block::compress_prepend_size(bytes)),
+        }
+    }
+
+    /// Decompresses the given bytes.
+    /// Returns `None` if the bytes are not a valid stream for this codec.
+    pub fn decompress(self, bytes: &[u8]) -> Option<Vec<u8>> {
+        self.decompress_limited(bytes, usize::MAX)
+    }
+
+    /// Decompresses the given bytes, giving up as soon as the output grows past `limit` bytes.
+    /// Returns `None` if the bytes are not a valid stream for this codec, or if they decompress to more than `limit` bytes.
+    pub fn decompress_limited(self, bytes: &[u8], limit: usize) -> Option<Vec<u8>> {
+        #[cfg(feature = "deflate")]
+        let take = (limit as u64).saturating_add(1);
+        let out = match self {
+            #[cfg(feature = "deflate")]
+            Codec::Deflate => {
+                let mut out = Vec::new();
+                flate2::read::DeflateDecoder::new(bytes).take(take).read_to_end(&mut out).ok()?;
+                out
+            }
+            #[cfg(feature = "deflate")]
+            Codec::Zlib => {
+                let mut out = Vec::new();
+                flate2::read::ZlibDecoder::new(bytes).take(take).read_to_end(&mut out).ok()?;
+                out
+            }
+            #[cfg(feature = "deflate")]
+            Codec::Gzip => {
+                let mut out = Vec::new();
+                flate2::read::GzDecoder::new(bytes).take(take).read_to_end(&mut out).ok()?;
+                out
+            }
+            #[cfg(feature = "lz4")]
+            Codec::Lz4 => {
+                let size = u32::from_le_bytes(bytes.get(..4)?.try_into().ok()?);
+                if usize::try_from(size).ok()? > limit {
+                    return None;
+                }
+                lz4_flex::block::decompress_size_prepended(bytes).ok()?
+            }
+        };
+        if out.len() > limit {
+            return None;
+        }
+        Some(out)
+    }
+}
+
+impl<S: Storage> Buffer<S> {
+
+    //#region Compression methods
+
+    /// Compresses the unread bytes of the buffer and writes them to `dest`.
+    /// Consumes the unread bytes. Returns the number of compressed bytes written.
+    pub fn compress_into<D: StorageMut>(&mut self, dest: &mut Buffer<D>, codec: Codec) -> Option<usize> {
+        let compressed = codec.compress(&self.as_slice()[self.position..])?;
+        dest.write_bytes(&compressed)?;
+        self.position = self.len();
+        Some(compressed.len())
+    }
+
+    /// Reads `len` compressed bytes from the buffer and decompresses them into a new buffer.
+    /// The decompressed size counts against the `max_bytes_length` and `max_total_allocation` limits.
+    /// The new buffer inherits the byte order, decode limits, allocation count and nesting depth,
+    /// so reads from it keep counting against this buffer's limits.
+    /// The position is left untouched if the bytes could not be decompressed.
+    pub fn read_compressed(&mut self, len: usize, codec: Codec) -> Option<Buffer> {
+        if len > self.remaining() {
+            return None;
+        }
+        let budget = self.decode_limits.max_total_allocation.saturating_sub(self.allocated);
+        let limit = self.decode_limits.max_bytes_length.min(budget);
+        let decompressed = codec.decompress_limited(&self.as_slice()[self.position..self.position + len], limit)?;
+        self.allocate(decompressed.len())?;
+        self.position += len;
+        let mut buffer = Buffer::wrap(decompressed);
+        buffer.byte_order = self.byte_order;
+        buffer.decode_limits = self.decode_limits;
+        buffer.allocated = self.allocated;
+        buffer.depth = self.depth;
+        Some(buffer)
+    }
+
+    //#endregion Compression methods
+}
@@ -0,0 +1,71 @@
+/// Limits enforced by every length-driven read, to protect against malicious length prefixes.
+///
+/// A read that would go past a limit fails the same way a read past the end of the buffer does.
+///
+/// ```rust
+/// use muscleman::Buffer::Buffer;
+/// use muscleman::DecodeLimits::DecodeLimits;
+///
+/// let mut buffer = Buffer::new();
+/// buffer.set_decode_limits(DecodeLimits { max_string_length: 16, ..DecodeLimits::new() });
+///
+/// buffer.write_string_with_length("This string is too long");
+/// assert_eq!(buffer.read_string_with_length(), None);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// The maximum length of a string, in bytes
+    pub max_string_length: usize,
+
+    /// The maximum length of a byte array
+    pub max_bytes_length: usize,
+
+    /// The maximum number of items in an array or map
+    pub max_collection_count: usize,
+
+    /// The maximum nesting depth of compound values
+    pub max_nesting_depth: usize,
+
+    /// The maximum number of bytes allocated by reads until the buffer's allocation counter is reset
+    pub max_total_allocation: usize,
+}
+
+impl DecodeLimits {
+
+    //#region Constants
+
+    // The default maximum nesting depth, low enough to never overflow the stack
+    pub const DEFAULT_MAX_NESTING_DEPTH: usize = 128;
+
+    //#endregion
+
+    //#region Constructors
+
+    // Default constructor
+    // Lengths are only bounded by the data in the buffer, nesting is bounded to protect the stack.
+    pub fn new() -> DecodeLimits {
+        DecodeLimits {
+            max_nesting_depth: DecodeLimits::DEFAULT_MAX_NESTING_DEPTH,
+            ..DecodeLimits::unlimited()
+        }
+    }
+
+    /// Creates limits that never fail a read.
+    pub fn unlimited() -> DecodeLimits {
+        DecodeLimits {
+            max_string_length: usize::MAX,
+            max_bytes_length: usize::MAX,
+            max_collection_count: usize::MAX,
+            max_nesting_depth: usize::MAX,
+            max_total_allocation: usize::MAX,
+        }
+    }
+
+    //#endregion Constructors
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits::new()
+    }
+}
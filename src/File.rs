@@ -0,0 +1,56 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::Buffer::Buffer;
+use crate::Storage::Storage;
+
+#[cfg(feature = "mmap")]
+use memmap2::Mmap;
+
+impl Buffer {
+
+    //#region File constructors
+
+    /// Creates a buffer holding the whole contents of a file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Buffer> {
+        Ok(Buffer::wrap(fs::read(path)?))
+    }
+
+    /// Creates a read-only buffer over a memory-mapped file, without copying it into memory.
+    ///
+    /// The operating system pages the file in as it is read, so files larger than memory can be decoded.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this process or any other, while the buffer is alive.
+    /// The mapping would change under the buffer's borrowed bytes, or reads past the new end would fault.
+    #[cfg(feature = "mmap")]
+    pub unsafe fn open_mmap<P: AsRef<Path>>(path: P) -> io::Result<Buffer<Mmap>> {
+        let file = fs::File::open(path)?;
+        // SAFETY: the mapping is read-only, and the caller guarantees the file isn't modified while it is mapped
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(Buffer::wrap(map))
+    }
+
+    //#endregion File constructors
+}
+
+impl<S: Storage> Buffer<S> {
+
+    /// Writes the bytes of the buffer to a file, replacing its contents.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.as_slice())
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl Storage for Mmap {
+    type Length = ();
+
+    fn full_length(&self) {}
+
+    fn written(&self, _length: ()) -> &[u8] {
+        self
+    }
+}
@@ -0,0 +1,110 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Buffer::Buffer;
+use crate::Storage::{Storage, StorageMut};
+
+// The digits written by the hex methods
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+// Gets the value of a hex digit of either case
+fn hex_value(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        b'A'..=b'F' => Some(digit - b'A' + 10),
+        _ => None,
+    }
+}
+
+// Decodes pairs of hex digits, failing on an odd number of digits or anything that isn't one
+fn decode_hex_digits(digits: &[u8]) -> Option<Vec<u8>> {
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    digits.chunks_exact(2).map(|pair| Some(hex_value(pair[0])? << 4 | hex_value(pair[1])?)).collect()
+}
+
+// Appends the lowercase hex digits of the bytes to a string
+fn encode_hex_digits(bytes: &[u8], out: &mut String) {
+    out.reserve(bytes.len() * 2);
+    for byte in bytes {
+        out.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+        out.push(HEX_DIGITS[(byte & 0x0F) as usize] as char);
+    }
+}
+
+impl Buffer {
+
+    //#region Hex constructors
+
+    /// Creates a buffer from hex text, such as `de ad be ef` or `0xDEAD 0xBEEF`.
+    /// Whitespace, including newlines, and `0x` prefixes are skipped, and digits can be of either case.
+    /// Fails on any other character, or an odd number of digits.
+    ///
+    /// ```rust
+    /// use muscleman::Buffer::Buffer;
+    ///
+    /// let buffer = Buffer::from_hex("0xde ad\nBE EF").unwrap();
+    /// assert_eq!(buffer.as_slice(), [0xDE, 0xAD, 0xBE, 0xEF]);
+    /// assert_eq!(buffer.to_hex(), "deadbeef");
+    /// ```
+    pub fn from_hex(text: &str) -> Option<Buffer> {
+        let text = text.as_bytes();
+        let mut digits = Vec::with_capacity(text.len());
+        let mut index = 0;
+        while index < text.len() {
+            match text[index] {
+                byte if byte.is_ascii_whitespace() => {}
+                // A prefix can only start a byte, so the 0 can't be a digit
+                b'0' if digits.len().is_multiple_of(2) && matches!(text.get(index + 1), Some(b'x' | b'X')) => index += 1,
+                byte => digits.push(byte),
+            }
+            index += 1;
+        }
+        decode_hex_digits(&digits).map(Buffer::wrap)
+    }
+
+    //#endregion Hex constructors
+}
+
+impl<S: Storage> Buffer<S> {
+
+    //#region Hex reading methods
+
+    /// Gets the buffer's contents as lowercase hex digits, without separators.
+    pub fn to_hex(&self) -> String {
+        let mut hex = String::new();
+        encode_hex_digits(self.as_slice(), &mut hex);
+        hex
+    }
+
+    /// Reads n ASCII hex digits of either case, decoding them into n / 2 bytes.
+    /// Fails without moving the position if n is odd, any of them isn't a hex digit,
+    /// or the decoded length is larger than the `max_bytes_length` limit.
+    pub fn read_hex_string(&mut self, n: usize) -> Option<Vec<u8>> {
+        if n / 2 > self.decode_limits.max_bytes_length {
+            return None;
+        }
+        let bytes = decode_hex_digits(self.peek_bytes(n)?)?;
+        self.allocate(bytes.len())?;
+        self.position += n;
+        Some(bytes)
+    }
+
+    //#endregion Hex reading methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region Hex writing methods
+
+    /// Writes bytes as lowercase ASCII hex digits, two per byte.
+    pub fn write_hex_string(&mut self, bytes: &[u8]) -> Option<()> {
+        let mut hex = String::new();
+        encode_hex_digits(bytes, &mut hex);
+        self.write_bytes(hex.as_bytes())
+    }
+
+    //#endregion Hex writing methods
+}
@@ -0,0 +1,119 @@
+use alloc::string::String;
+use alloc::vec;
+use core::fmt::Write;
+use core::ops::Range;
+
+use crate::Buffer::Buffer;
+use crate::Storage::Storage;
+
+/// Options for rendering a hex dump of a buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HexDumpOptions {
+    /// The range of bytes to dump. Dumps the whole buffer when `None`.
+    pub range: Option<Range<usize>>,
+
+    /// The number of bytes shown per line, from 1 to `MAX_WIDTH`
+    pub width: usize,
+
+    /// The number of bytes per group. Groups are separated by an extra space.
+    pub group: usize,
+
+    /// Whether the position and mark are highlighted
+    pub annotate: bool,
+}
+
+impl HexDumpOptions {
+
+    //#region Constants
+
+    // The default number of bytes per line
+    pub const DEFAULT_WIDTH: usize = 16;
+
+    // The default number of bytes per group
+    pub const DEFAULT_GROUP: usize = 8;
+
+    /// The largest number of bytes shown per line. Wider widths are clamped to it.
+    pub const MAX_WIDTH: usize = 256;
+
+    //#endregion
+
+    // Default constructor
+    pub fn new() -> HexDumpOptions {
+        HexDumpOptions {
+            range: None,
+            width: HexDumpOptions::DEFAULT_WIDTH,
+            group: HexDumpOptions::DEFAULT_GROUP,
+            annotate: true,
+        }
+    }
+}
+
+impl Default for HexDumpOptions {
+    fn default() -> Self {
+        HexDumpOptions::new()
+    }
+}
+
+impl<S: Storage> Buffer<S> {
+
+    //#region Hex dump methods
+
+    /// Renders the whole buffer as offset/hex/ASCII columns.
+    /// The current position and mark are highlighted below the line they point into.
+    pub fn hexdump(&self) -> String {
+        self.hexdump_with_options(&HexDumpOptions::new())
+    }
+
+    /// Renders the buffer as offset/hex/ASCII columns using the given options.
+    pub fn hexdump_with_options(&self, options: &HexDumpOptions) -> String {
+        let width = options.width.clamp(1, HexDumpOptions::MAX_WIDTH);
+        let group = if options.group == 0 { width } else { options.group };
+        let range = options.range.clone().unwrap_or(0..self.len());
+        let end = range.end.min(self.len());
+        let start = range.start.min(end);
+
+        let mut out = String::new();
+        let mut line_start = start;
+        while line_start < end {
+            let line_end = line_start.saturating_add(width).min(end);
+            let bytes = &self.as_slice()[line_start..line_end];
+
+            write!(out, "{:08x}  ", line_start).unwrap();
+            for i in 0..width {
+                match bytes.get(i) {
+                    Some(byte) => write!(out, "{:02x} ", byte).unwrap(),
+                    None => out.push_str("   "),
+                }
+                if (i + 1) % group == 0 && i + 1 < width {
+                    out.push(' ');
+                }
+            }
+            out.push_str(" |");
+            for byte in bytes {
+                out.push(if byte.is_ascii_graphic() || *byte == b' ' { *byte as char } else { '.' });
+            }
+            out.push_str("|\n");
+
+            if options.annotate {
+                let last_line = line_end == end;
+                let mut markers = vec![("position", self.position)];
+                if let Some(mark) = self.mark {
+                    markers.push(("mark", mark));
+                }
+                for (label, offset) in markers {
+                    let on_line = (line_start..line_end).contains(&offset) || (last_line && offset == end);
+                    if on_line {
+                        let i = offset - line_start;
+                        let column = 10 + i * 3 + i / group;
+                        writeln!(out, "{}^^ {} ({:#x})", " ".repeat(column), label, offset).unwrap();
+                    }
+                }
+            }
+
+            line_start = line_end;
+        }
+        out
+    }
+
+    //#endregion Hex dump methods
+}
@@ -0,0 +1,434 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Buffer::Buffer;
+use crate::ByteOrder::ByteOrder;
+use crate::Storage::{Storage, StorageMut};
+use crate::Value::Value;
+
+// MessagePack is always big-endian, so every method switches the buffer to big-endian while it runs.
+// Typed reads fail without consuming anything if the value is a different type, cut short or invalid.
+
+impl<S: Storage> Buffer<S> {
+
+    //#region MessagePack reading methods
+
+    /// Reads a MessagePack nil.
+    pub fn read_msgpack_nil(&mut self) -> Option<()> {
+        match self.peek_byte()? {
+            0xC0 => self.read_u8().map(|_| ()),
+            _ => None,
+        }
+    }
+
+    /// Reads a MessagePack boolean.
+    pub fn read_msgpack_bool(&mut self) -> Option<bool> {
+        let value = match self.peek_byte()? {
+            0xC2 => false,
+            0xC3 => true,
+            _ => return None,
+        };
+        self.read_u8()?;
+        Some(value)
+    }
+
+    /// Reads any MessagePack integer that fits in an `i64`.
+    pub fn read_msgpack_int(&mut self) -> Option<i64> {
+        self.atomic(|buffer| match buffer.read_msgpack_integer()? {
+            Value::Int(value) => Some(value),
+            Value::UInt(value) => i64::try_from(value).ok(),
+            _ => None,
+        })
+    }
+
+    /// Reads any MessagePack integer that fits in a `u64`.
+    pub fn read_msgpack_uint(&mut self) -> Option<u64> {
+        self.atomic(|buffer| match buffer.read_msgpack_integer()? {
+            Value::Int(value) => u64::try_from(value).ok(),
+            Value::UInt(value) => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Reads a MessagePack float 32.
+    pub fn read_msgpack_f32(&mut self) -> Option<f32> {
+        self.atomic(|buffer| {
+            match buffer.peek_byte()? {
+                0xCA => buffer.with_byte_order(ByteOrder::BigEndian, |buffer| {
+                    buffer.read_u8()?;
+                    buffer.read_f32()
+                }),
+                _ => None,
+            }
+        })
+    }
+
+    /// Reads a MessagePack float 32 or float 64.
+    pub fn read_msgpack_f64(&mut self) -> Option<f64> {
+        self.atomic(|buffer| {
+            match buffer.peek_byte()? {
+                0xCA => buffer.read_msgpack_f32().map(f64::from),
+                0xCB => buffer.with_byte_order(ByteOrder::BigEndian, |buffer| {
+                    buffer.read_u8()?;
+                    buffer.read_f64()
+                }),
+                _ => None,
+            }
+        })
+    }
+
+    /// Reads a MessagePack string.
+    /// Fails if the string is longer than the `max_string_length` limit, or isn't valid UTF-8.
+    pub fn read_msgpack_str(&mut self) -> Option<String> {
+        self.atomic(|buffer| {
+            let length = match buffer.peek_byte()? {
+                0xA0..=0xBF | 0xD9..=0xDB => buffer.read_msgpack_length()?,
+                _ => return None,
+            };
+            let bytes = buffer.read_limited_bytes(length, buffer.decode_limits.max_string_length)?;
+            String::from_utf8(bytes).ok()
+        })
+    }
+
+    /// Reads MessagePack binary data.
+    /// Fails if the data is longer than the `max_bytes_length` limit.
+    pub fn read_msgpack_bin(&mut self) -> Option<Vec<u8>> {
+        self.atomic(|buffer| {
+            let length = match buffer.peek_byte()? {
+                0xC4..=0xC6 => buffer.read_msgpack_length()?,
+                _ => return None,
+            };
+            buffer.read_bytes(length)
+        })
+    }
+
+    /// Reads the header of a MessagePack array, returning its number of items.
+    /// Fails if the count is larger than the `max_collection_count` limit.
+    pub fn read_msgpack_array_len(&mut self) -> Option<usize> {
+        self.atomic(|buffer| {
+            match buffer.peek_byte()? {
+                0x90..=0x9F | 0xDC | 0xDD => {}
+                _ => return None,
+            }
+            let count = buffer.read_msgpack_length()?;
+            buffer.check_collection_count(count)?;
+            Some(count)
+        })
+    }
+
+    /// Reads the header of a MessagePack map, returning its number of key-value pairs.
+    /// Fails if the count is larger than the `max_collection_count` limit.
+    pub fn read_msgpack_map_len(&mut self) -> Option<usize> {
+        self.atomic(|buffer| {
+            match buffer.peek_byte()? {
+                0x80..=0x8F | 0xDE | 0xDF => {}
+                _ => return None,
+            }
+            let count = buffer.read_msgpack_length()?;
+            buffer.check_collection_count(count)?;
+            Some(count)
+        })
+    }
+
+    /// Reads a MessagePack extension, returning its type and data.
+    /// Fails if the data is longer than the `max_bytes_length` limit.
+    pub fn read_msgpack_ext(&mut self) -> Option<(i8, Vec<u8>)> {
+        self.atomic(|buffer| {
+            let length = match buffer.peek_byte()? {
+                0xD4..=0xD8 | 0xC7..=0xC9 => buffer.read_msgpack_length()?,
+                _ => return None,
+            };
+            let ext_type = buffer.read_i8()?;
+            Some((ext_type, buffer.read_bytes(length)?))
+        })
+    }
+
+    /// Reads any MessagePack value.
+    /// Maps whose keys are all strings become structs, other maps become `Value::Map`.
+    pub fn read_msgpack_value(&mut self) -> Option<Value> {
+        self.atomic(|buffer| {
+            match buffer.peek_byte()? {
+                0xC0 => buffer.read_msgpack_nil().map(|_| Value::Null),
+                0xC2 | 0xC3 => buffer.read_msgpack_bool().map(Value::Bool),
+                0x00..=0x7F | 0xE0..=0xFF | 0xCC..=0xD3 => buffer.read_msgpack_integer(),
+                0xCA | 0xCB => buffer.read_msgpack_f64().map(Value::Float),
+                0xA0..=0xBF | 0xD9..=0xDB => buffer.read_msgpack_str().map(Value::String),
+                0xC4..=0xC6 => buffer.read_msgpack_bin().map(Value::Bytes),
+                0xD4..=0xD8 | 0xC7..=0xC9 => buffer.read_msgpack_ext().map(|(ext_type, data)| Value::Ext(ext_type, data)),
+                0x90..=0x9F | 0xDC | 0xDD => {
+                    let count = buffer.read_msgpack_array_len()?;
+                    buffer.read_msgpack_items(count).map(Value::Array)
+                }
+                0x80..=0x8F | 0xDE | 0xDF => {
+                    let count = buffer.read_msgpack_map_len()?;
+                    let entries = buffer.read_msgpack_items(count.checked_mul(2)?)?;
+                    Some(Value::from_alternating(entries))
+                }
+                // 0xC1 is never used
+                _ => None,
+            }
+        })
+    }
+
+    // Reads the items of an array, or the keys and values of a map, one level deeper
+    fn read_msgpack_items(&mut self, count: usize) -> Option<Vec<Value>> {
+        self.enter_nested()?;
+        let mut items = Vec::new();
+        for _ in 0..count {
+            let item = self.allocate(core::mem::size_of::<Value>()).and_then(|_| self.read_msgpack_value());
+            match item {
+                Some(item) => items.push(item),
+                None => {
+                    self.exit_nested();
+                    return None;
+                }
+            }
+        }
+        self.exit_nested();
+        Some(items)
+    }
+
+    // Reads an integer of any family, as a `Value::UInt` for unsigned families and a `Value::Int` for signed ones
+    fn read_msgpack_integer(&mut self) -> Option<Value> {
+        self.atomic(|buffer| buffer.with_byte_order(ByteOrder::BigEndian, |buffer| {
+            Some(match buffer.read_u8()? {
+                marker @ 0x00..=0x7F => Value::UInt(marker as u64),
+                marker @ 0xE0..=0xFF => Value::Int(marker as i8 as i64),
+                0xCC => Value::UInt(buffer.read_u8()? as u64),
+                0xCD => Value::UInt(buffer.read_u16()? as u64),
+                0xCE => Value::UInt(buffer.read_u32()? as u64),
+                0xCF => Value::UInt(buffer.read_u64()?),
+                0xD0 => Value::Int(buffer.read_i8()? as i64),
+                0xD1 => Value::Int(buffer.read_i16()? as i64),
+                0xD2 => Value::Int(buffer.read_i32()? as i64),
+                0xD3 => Value::Int(buffer.read_i64()?),
+                _ => return None,
+            })
+        }))
+    }
+
+    // Reads the format byte of a length-prefixed type and returns its length or count
+    fn read_msgpack_length(&mut self) -> Option<usize> {
+        let length = self.with_byte_order(ByteOrder::BigEndian, |buffer| {
+            Some(match buffer.read_u8()? {
+                // fixmap and fixarray
+                marker @ 0x80..=0x9F => (marker & 0x0F) as u32,
+                marker @ 0xA0..=0xBF => (marker & 0x1F) as u32,
+                0xD4 => 1,
+                0xD5 => 2,
+                0xD6 => 4,
+                0xD7 => 8,
+                0xD8 => 16,
+                0xC4 | 0xC7 | 0xD9 => buffer.read_u8()? as u32,
+                0xC5 | 0xC8 | 0xDA | 0xDC | 0xDE => buffer.read_u16()? as u32,
+                0xC6 | 0xC9 | 0xDB | 0xDD | 0xDF => buffer.read_u32()?,
+                _ => return None,
+            })
+        })?;
+        usize::try_from(length).ok()
+    }
+
+    //#endregion MessagePack reading methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region MessagePack writing methods
+
+    /// Writes a MessagePack nil.
+    pub fn write_msgpack_nil(&mut self) -> Option<()> {
+        self.write_u8(0xC0)
+    }
+
+    /// Writes a MessagePack boolean.
+    pub fn write_msgpack_bool(&mut self, value: bool) -> Option<()> {
+        self.write_u8(if value { 0xC3 } else { 0xC2 })
+    }
+
+    /// Writes a signed integer, using the smallest format that holds it.
+    pub fn write_msgpack_int(&mut self, value: i64) -> Option<()> {
+        if value >= 0 {
+            return self.write_msgpack_uint(value as u64);
+        }
+        self.write_msgpack_header(|header| {
+            if value >= -32 {
+                header.write_i8(value as i8)
+            } else if let Ok(value) = i8::try_from(value) {
+                header.write_u8(0xD0)?;
+                header.write_i8(value)
+            } else if let Ok(value) = i16::try_from(value) {
+                header.write_u8(0xD1)?;
+                header.write_i16(value)
+            } else if let Ok(value) = i32::try_from(value) {
+                header.write_u8(0xD2)?;
+                header.write_i32(value)
+            } else {
+                header.write_u8(0xD3)?;
+                header.write_i64(value)
+            }
+        }, &[])
+    }
+
+    /// Writes an unsigned integer, using the smallest format that holds it.
+    pub fn write_msgpack_uint(&mut self, value: u64) -> Option<()> {
+        self.write_msgpack_header(|header| {
+            if value < 0x80 {
+                header.write_u8(value as u8)
+            } else if let Ok(value) = u8::try_from(value) {
+                header.write_u8(0xCC)?;
+                header.write_u8(value)
+            } else if let Ok(value) = u16::try_from(value) {
+                header.write_u8(0xCD)?;
+                header.write_u16(value)
+            } else if let Ok(value) = u32::try_from(value) {
+                header.write_u8(0xCE)?;
+                header.write_u32(value)
+            } else {
+                header.write_u8(0xCF)?;
+                header.write_u64(value)
+            }
+        }, &[])
+    }
+
+    /// Writes a MessagePack float 32.
+    pub fn write_msgpack_f32(&mut self, value: f32) -> Option<()> {
+        self.write_msgpack_header(|header| {
+            header.write_u8(0xCA)?;
+            header.write_f32(value)
+        }, &[])
+    }
+
+    /// Writes a MessagePack float 64.
+    pub fn write_msgpack_f64(&mut self, value: f64) -> Option<()> {
+        self.write_msgpack_header(|header| {
+            header.write_u8(0xCB)?;
+            header.write_f64(value)
+        }, &[])
+    }
+
+    /// Writes a MessagePack string, using the smallest format that holds its length.
+    pub fn write_msgpack_str(&mut self, value: &str) -> Option<()> {
+        let length = u32::try_from(value.len()).ok()?;
+        self.write_msgpack_header(|header| {
+            if length < 32 {
+                header.write_u8(0xA0 | length as u8)
+            } else {
+                write_msgpack_size(header, [0xD9, 0xDA, 0xDB], length)
+            }
+        }, value.as_bytes())
+    }
+
+    /// Writes MessagePack binary data, using the smallest format that holds its length.
+    pub fn write_msgpack_bin(&mut self, value: &[u8]) -> Option<()> {
+        let length = u32::try_from(value.len()).ok()?;
+        self.write_msgpack_header(|header| write_msgpack_size(header, [0xC4, 0xC5, 0xC6], length), value)
+    }
+
+    /// Writes the header of a MessagePack array with `count` items. The items are written after it.
+    pub fn write_msgpack_array_len(&mut self, count: u32) -> Option<()> {
+        self.write_msgpack_header(|header| {
+            if count < 16 {
+                header.write_u8(0x90 | count as u8)
+            } else if let Ok(count) = u16::try_from(count) {
+                header.write_u8(0xDC)?;
+                header.write_u16(count)
+            } else {
+                header.write_u8(0xDD)?;
+                header.write_u32(count)
+            }
+        }, &[])
+    }
+
+    /// Writes the header of a MessagePack map with `count` key-value pairs. The keys and values are written after it.
+    pub fn write_msgpack_map_len(&mut self, count: u32) -> Option<()> {
+        self.write_msgpack_header(|header| {
+            if count < 16 {
+                header.write_u8(0x80 | count as u8)
+            } else if let Ok(count) = u16::try_from(count) {
+                header.write_u8(0xDE)?;
+                header.write_u16(count)
+            } else {
+                header.write_u8(0xDF)?;
+                header.write_u32(count)
+            }
+        }, &[])
+    }
+
+    /// Writes a MessagePack extension, using a fixext format when the data has a fixed size.
+    pub fn write_msgpack_ext(&mut self, ext_type: i8, data: &[u8]) -> Option<()> {
+        let length = u32::try_from(data.len()).ok()?;
+        self.write_msgpack_header(|header| {
+            match length {
+                1 => header.write_u8(0xD4)?,
+                2 => header.write_u8(0xD5)?,
+                4 => header.write_u8(0xD6)?,
+                8 => header.write_u8(0xD7)?,
+                16 => header.write_u8(0xD8)?,
+                _ => write_msgpack_size(header, [0xC7, 0xC8, 0xC9], length)?,
+            }
+            header.write_i8(ext_type)
+        }, data)
+    }
+
+    /// Writes any value as MessagePack. Structs are written as maps with string keys.
+    /// Fails on tags and simple values, or if a collection has more than `u32::MAX` items, leaving what was written so far.
+    pub fn write_msgpack_value(&mut self, value: &Value) -> Option<()> {
+        match value {
+            Value::Null => self.write_msgpack_nil(),
+            Value::Bool(value) => self.write_msgpack_bool(*value),
+            Value::Int(value) => self.write_msgpack_int(*value),
+            Value::UInt(value) => self.write_msgpack_uint(*value),
+            Value::Float(value) => self.write_msgpack_f64(*value),
+            Value::String(value) => self.write_msgpack_str(value),
+            Value::Bytes(value) => self.write_msgpack_bin(value),
+            Value::Ext(ext_type, data) => self.write_msgpack_ext(*ext_type, data),
+            // MessagePack has no tags or other simple values
+            Value::Tag(..) | Value::Simple(_) => None,
+            Value::Array(items) => {
+                self.write_msgpack_array_len(u32::try_from(items.len()).ok()?)?;
+                items.iter().try_for_each(|item| self.write_msgpack_value(item))
+            }
+            Value::Struct(fields) => {
+                self.write_msgpack_map_len(u32::try_from(fields.len()).ok()?)?;
+                fields.iter().try_for_each(|(name, value)| {
+                    self.write_msgpack_str(name)?;
+                    self.write_msgpack_value(value)
+                })
+            }
+            Value::Map(entries) => {
+                self.write_msgpack_map_len(u32::try_from(entries.len()).ok()?)?;
+                entries.iter().try_for_each(|(key, value)| {
+                    self.write_msgpack_value(key)?;
+                    self.write_msgpack_value(value)
+                })
+            }
+        }
+    }
+
+    // Builds a big-endian header, then writes it and the payload only if both fit
+    fn write_msgpack_header(&mut self, build: impl FnOnce(&mut Buffer<[u8; 10]>) -> Option<()>, payload: &[u8]) -> Option<()> {
+        let mut header = Buffer::wrap_empty([0; 10]);
+        build(&mut header)?;
+        if !self.has_space(header.len().checked_add(payload.len())?) {
+            return None;
+        }
+        self.write_bytes(header.as_slice())?;
+        self.write_bytes(payload)
+    }
+
+    //#endregion MessagePack writing methods
+}
+
+// Writes the 8, 16 or 32-bit format byte and size, whichever is the smallest that holds the size
+fn write_msgpack_size(header: &mut Buffer<[u8; 10]>, markers: [u8; 3], size: u32) -> Option<()> {
+    if let Ok(size) = u8::try_from(size) {
+        header.write_u8(markers[0])?;
+        header.write_u8(size)
+    } else if let Ok(size) = u16::try_from(size) {
+        header.write_u8(markers[1])?;
+        header.write_u16(size)
+    } else {
+        header.write_u8(markers[2])?;
+        header.write_u32(size)
+    }
+}
@@ -0,0 +1,327 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Buffer::Buffer;
+use crate::ByteOrder::ByteOrder;
+#[cfg(feature = "deflate")]
+use crate::Compression::Codec;
+use crate::Storage::{Storage, StorageMut};
+
+// The Minecraft protocol is always big-endian, whatever the buffer's byte order
+
+/// The longest string allowed by the protocol, in UTF-16 code units
+pub const MAX_STRING_LENGTH: usize = 32767;
+
+/// The longest packet allowed by the protocol, the most a 3-byte VarInt can hold
+pub const MAX_PACKET_LENGTH: usize = (1 << 21) - 1;
+
+/// The largest uncompressed size a compressed packet may have
+pub const MAX_UNCOMPRESSED_LENGTH: usize = 1 << 23;
+
+/// A block position, packed into 64 bits as 26 bits of x, 26 bits of z and 12 bits of y.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Position {
+    /// Unpacks a position from its 64 bits, sign-extending each coordinate.
+    pub fn from_packed(packed: u64) -> Position {
+        let packed = packed as i64;
+        Position {
+            x: (packed >> 38) as i32,
+            y: (packed << 52 >> 52) as i32,
+            z: (packed << 26 >> 38) as i32,
+        }
+    }
+
+    /// Packs the position into 64 bits.
+    /// Fails if x or z is outside ±2^25, or y is outside ±2^11.
+    pub fn to_packed(self) -> Option<u64> {
+        let fits = |value: i32, bits: u32| (-(1 << (bits - 1))..1 << (bits - 1)).contains(&value);
+        if !fits(self.x, 26) || !fits(self.z, 26) || !fits(self.y, 12) {
+            return None;
+        }
+        Some(((self.x as u64 & 0x3FF_FFFF) << 38) | ((self.z as u64 & 0x3FF_FFFF) << 12) | (self.y as u64 & 0xFFF))
+    }
+}
+
+// Checks an identifier is `namespace:path` or a bare path, using only the characters the protocol allows
+fn is_valid_identifier(identifier: &str) -> bool {
+    let (namespace, path) = identifier.split_once(':').unwrap_or(("minecraft", identifier));
+    let valid = |byte: u8| matches!(byte, b'a'..=b'z' | b'0'..=b'9' | b'.' | b'_' | b'-');
+    !path.is_empty() && namespace.bytes().all(valid) && path.bytes().all(|byte| byte == b'/' || valid(byte))
+}
+
+impl<S: Storage> Buffer<S> {
+
+    //#region Minecraft reading methods
+
+    /// Reads a VarInt, a 32-bit integer in at most 5 bytes.
+    pub fn read_mc_varint(&mut self) -> Option<i32> {
+        let mut result: u32 = 0;
+        for (index, byte) in self.unread().iter().take(5).enumerate() {
+            result |= ((byte & 0x7F) as u32) << (7 * index);
+            if byte & 0x80 == 0 {
+                self.position += index + 1;
+                return Some(result as i32);
+            }
+        }
+        None
+    }
+
+    /// Reads a VarLong, a 64-bit integer in at most 10 bytes.
+    pub fn read_mc_varlong(&mut self) -> Option<i64> {
+        self.read_varint()
+    }
+
+    /// Reads a UTF-8 string prefixed with its length in bytes as a VarInt.
+    /// Fails if the string is longer than `max_length` UTF-16 code units, or than the `max_string_length` limit in bytes.
+    pub fn read_mc_string(&mut self, max_length: usize) -> Option<String> {
+        self.atomic(|buffer| {
+            let length = usize::try_from(buffer.read_mc_varint()?).ok()?;
+            // A UTF-16 code unit takes at most 3 bytes of UTF-8
+            if length > max_length.saturating_mul(3) {
+                return None;
+            }
+            let bytes = buffer.read_limited_bytes(length, buffer.decode_limits.max_string_length)?;
+            let string = String::from_utf8(bytes).ok()?;
+            if string.encode_utf16().count() > max_length {
+                return None;
+            }
+            Some(string)
+        })
+    }
+
+    /// Reads a block position packed into a 64-bit integer.
+    pub fn read_mc_position(&mut self) -> Option<Position> {
+        self.take().map(u64::from_be_bytes).map(Position::from_packed)
+    }
+
+    /// Reads an angle in steps of 1/256 of a turn, returning it in degrees.
+    pub fn read_mc_angle(&mut self) -> Option<f32> {
+        self.read_u8().map(|steps| steps as f32 * 360.0 / 256.0)
+    }
+
+    /// Reads a UUID sent as two big-endian 64-bit integers, most significant first.
+    pub fn read_mc_uuid(&mut self) -> Option<u128> {
+        self.take().map(u128::from_be_bytes)
+    }
+
+    /// Reads an identifier such as `minecraft:stone`. Fails if it contains a character the protocol doesn't allow.
+    pub fn read_mc_identifier(&mut self) -> Option<String> {
+        self.atomic(|buffer| {
+            buffer.read_mc_string(MAX_STRING_LENGTH).filter(|identifier| is_valid_identifier(identifier))
+        })
+    }
+
+    /// Reads a bit set prefixed with its number of 64-bit words as a VarInt.
+    /// Bit n is bit `n % 64` of word `n / 64`.
+    pub fn read_mc_bitset(&mut self) -> Option<Vec<u64>> {
+        self.atomic(|buffer| {
+            let count = usize::try_from(buffer.read_mc_varint()?).ok()?;
+            buffer.with_byte_order(ByteOrder::BigEndian, |buffer| buffer.read_u64_vec(count))
+        })
+    }
+
+    /// Reads a bit set of a length known in advance, taking `bits / 8` bytes rounded up.
+    /// Bit n is bit `n % 8` of byte `n / 8`.
+    pub fn read_mc_fixed_bitset(&mut self, bits: usize) -> Option<Vec<u8>> {
+        self.read_bytes(bits.div_ceil(8))
+    }
+
+    /// Reads a value prefixed with a boolean telling whether it is present.
+    pub fn read_mc_optional<T>(&mut self, read: impl FnOnce(&mut Self) -> Option<T>) -> Option<Option<T>> {
+        self.atomic(|buffer| {
+            if buffer.read_boolean()? {
+                read(buffer).map(Some)
+            } else {
+                Some(None)
+            }
+        })
+    }
+
+    /// Reads a packet framed with its length, returning its packet ID and a buffer over its data.
+    /// With a compression threshold, reads the format used once compression is enabled. Compressed data needs the `deflate` feature.
+    /// Fails without moving the position if the whole packet hasn't been received yet, or it is malformed.
+    pub fn read_mc_packet(&mut self, compression_threshold: Option<usize>) -> Option<(i32, Buffer)> {
+        self.atomic(|buffer| buffer.read_mc_packet_frame(compression_threshold))
+    }
+
+    // Reads a packet, leaving the position wherever it failed
+    fn read_mc_packet_frame(&mut self, compression_threshold: Option<usize>) -> Option<(i32, Buffer)> {
+        let length = usize::try_from(self.read_mc_varint()?).ok()?;
+        if length > MAX_PACKET_LENGTH {
+            return None;
+        }
+        let budget = self.decode_limits.max_total_allocation.saturating_sub(self.allocated);
+        let limit = self.decode_limits.max_bytes_length.min(budget);
+        let mut frame = Buffer::wrap(self.take_slice(length)?);
+        let contents = match compression_threshold {
+            None => frame.unread().to_vec(),
+            Some(threshold) => match usize::try_from(frame.read_mc_varint()?).ok()? {
+                // A data length of 0 means the packet was below the threshold and sent uncompressed
+                0 => frame.unread().to_vec(),
+                #[cfg(feature = "deflate")]
+                data_length => {
+                    if data_length < threshold || data_length > MAX_UNCOMPRESSED_LENGTH || data_length > limit {
+                        return None;
+                    }
+                    let contents = Codec::Zlib.decompress_limited(frame.unread(), data_length)?;
+                    if contents.len() != data_length {
+                        return None;
+                    }
+                    contents
+                }
+                #[cfg(not(feature = "deflate"))]
+                _ => {
+                    // Compressed data can't be read without the `deflate` feature
+                    let _ = threshold;
+                    return None;
+                }
+            },
+        };
+        if contents.len() > limit {
+            return None;
+        }
+        self.allocate(contents.len())?;
+        let mut packet = Buffer::wrap(contents);
+        packet.set_decode_limits(self.decode_limits);
+        let packet_id = packet.read_mc_varint()?;
+        Some((packet_id, packet))
+    }
+
+    //#endregion Minecraft reading methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region Minecraft writing methods
+
+    /// Writes a VarInt, a 32-bit integer in at most 5 bytes.
+    pub fn write_mc_varint(&mut self, value: i32) -> Option<()> {
+        self.write_varint(value as u32 as i64)
+    }
+
+    /// Writes a VarLong, a 64-bit integer in at most 10 bytes.
+    pub fn write_mc_varlong(&mut self, value: i64) -> Option<()> {
+        self.write_varint(value)
+    }
+
+    /// Writes a UTF-8 string prefixed with its length in bytes as a VarInt.
+    /// Fails if the string is longer than `max_length` UTF-16 code units.
+    pub fn write_mc_string(&mut self, string: &str, max_length: usize) -> Option<()> {
+        if string.encode_utf16().count() > max_length {
+            return None;
+        }
+        let mut prefix = Buffer::wrap_empty([0; 5]);
+        prefix.write_mc_varint(i32::try_from(string.len()).ok()?)?;
+        if !self.has_space(prefix.len().checked_add(string.len())?) {
+            return None;
+        }
+        self.write_bytes(prefix.as_slice())?;
+        self.write_bytes(string.as_bytes())
+    }
+
+    /// Writes a block position packed into a 64-bit integer. Fails if a coordinate is out of range.
+    pub fn write_mc_position(&mut self, position: Position) -> Option<()> {
+        self.write_bytes(&position.to_packed()?.to_be_bytes())
+    }
+
+    /// Writes an angle in degrees as steps of 1/256 of a turn, rounding to the nearest step.
+    /// Angles outside a single turn wrap around. Fails if the angle is infinite or NaN.
+    pub fn write_mc_angle(&mut self, degrees: f32) -> Option<()> {
+        if !degrees.is_finite() {
+            return None;
+        }
+        let steps = degrees as f64 * 256.0 / 360.0;
+        let steps = if steps >= 0.0 { steps + 0.5 } else { steps - 0.5 } as i64;
+        self.write_u8(steps as u8)
+    }
+
+    /// Writes a UUID as two big-endian 64-bit integers, most significant first.
+    pub fn write_mc_uuid(&mut self, uuid: u128) -> Option<()> {
+        self.write_bytes(&uuid.to_be_bytes())
+    }
+
+    /// Writes an identifier such as `minecraft:stone`. Fails if it contains a character the protocol doesn't allow.
+    pub fn write_mc_identifier(&mut self, identifier: &str) -> Option<()> {
+        if !is_valid_identifier(identifier) {
+            return None;
+        }
+        self.write_mc_string(identifier, MAX_STRING_LENGTH)
+    }
+
+    /// Writes a bit set prefixed with its number of 64-bit words as a VarInt.
+    pub fn write_mc_bitset(&mut self, words: &[u64]) -> Option<()> {
+        let mut prefix = Buffer::wrap_empty([0; 5]);
+        prefix.write_mc_varint(i32::try_from(words.len()).ok()?)?;
+        if !self.has_space(words.len().checked_mul(8)?.checked_add(prefix.len())?) {
+            return None;
+        }
+        self.write_bytes(prefix.as_slice())?;
+        self.with_byte_order(ByteOrder::BigEndian, |buffer| buffer.write_u64_slice(words))
+    }
+
+    /// Writes a bit set of a length known in advance. Fails if it isn't `bits / 8` bytes rounded up.
+    pub fn write_mc_fixed_bitset(&mut self, bits: usize, bytes: &[u8]) -> Option<()> {
+        if bytes.len() != bits.div_ceil(8) {
+            return None;
+        }
+        self.write_bytes(bytes)
+    }
+
+    /// Writes a value prefixed with a boolean telling whether it is present.
+    pub fn write_mc_optional<T>(&mut self, value: Option<T>, write: impl FnOnce(&mut Self, T) -> Option<()>) -> Option<()> {
+        match value {
+            Some(value) => {
+                self.write_u8(1)?;
+                write(self, value)
+            }
+            None => self.write_u8(0),
+        }
+    }
+
+    /// Writes a packet framed with its length, starting with its packet ID.
+    /// With a compression threshold, packets at least that long are zlib compressed, which needs the `deflate` feature.
+    /// Fails if the packet is too long for the protocol, and then writes nothing.
+    pub fn write_mc_packet(&mut self, packet_id: i32, data: &[u8], compression_threshold: Option<usize>) -> Option<()> {
+        let mut contents = Buffer::new();
+        contents.write_mc_varint(packet_id)?;
+        contents.write_bytes(data)?;
+
+        let mut frame = Buffer::new();
+        match compression_threshold {
+            None => frame.write_bytes(contents.as_slice())?,
+            Some(threshold) if contents.len() < threshold => {
+                frame.write_mc_varint(0)?;
+                frame.write_bytes(contents.as_slice())?;
+            }
+            #[cfg(feature = "deflate")]
+            Some(_) => {
+                if contents.len() > MAX_UNCOMPRESSED_LENGTH {
+                    return None;
+                }
+                frame.write_mc_varint(contents.len() as i32)?;
+                frame.write_bytes(&Codec::Zlib.compress(contents.as_slice())?)?;
+            }
+            #[cfg(not(feature = "deflate"))]
+            Some(_) => return None,
+        }
+        if frame.len() > MAX_PACKET_LENGTH {
+            return None;
+        }
+
+        let mut prefix = Buffer::wrap_empty([0; 5]);
+        prefix.write_mc_varint(frame.len() as i32)?;
+        if !self.has_space(prefix.len() + frame.len()) {
+            return None;
+        }
+        self.write_bytes(prefix.as_slice())?;
+        self.write_bytes(frame.as_slice())
+    }
+
+    //#endregion Minecraft writing methods
+}
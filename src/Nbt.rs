@@ -0,0 +1,491 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "deflate")]
+use std::io;
+#[cfg(feature = "deflate")]
+use std::path::Path;
+
+use crate::Buffer::Buffer;
+use crate::ByteOrder::ByteOrder;
+#[cfg(feature = "deflate")]
+use crate::Compression::Codec;
+use crate::Storage::{Storage, StorageMut};
+
+/// The encoding of an NBT tree, which differs between Minecraft editions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NbtFormat {
+    /// Java Edition: big-endian, with strings in Java's modified UTF-8
+    Java,
+
+    /// Bedrock Edition files: little-endian
+    Bedrock,
+
+    /// Bedrock Edition network protocol: little-endian, with ints, longs and lengths as VarInts
+    BedrockNetwork,
+}
+
+impl NbtFormat {
+    // The byte order of fixed-width numbers
+    fn byte_order(self) -> ByteOrder {
+        match self {
+            NbtFormat::Java => ByteOrder::BigEndian,
+            NbtFormat::Bedrock | NbtFormat::BedrockNetwork => ByteOrder::LittleEndian,
+        }
+    }
+}
+
+/// An NBT tag, one variant per tag type. Compounds keep their entries in order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NbtTag {
+    /// Tag type 1
+    Byte(i8),
+
+    /// Tag type 2
+    Short(i16),
+
+    /// Tag type 3
+    Int(i32),
+
+    /// Tag type 4
+    Long(i64),
+
+    /// Tag type 5
+    Float(f32),
+
+    /// Tag type 6
+    Double(f64),
+
+    /// Tag type 7
+    ByteArray(Vec<i8>),
+
+    /// Tag type 8
+    String(String),
+
+    /// Tag type 9, whose items all have the same type. An empty list is written with the end tag type.
+    List(Vec<NbtTag>),
+
+    /// Tag type 10
+    Compound(Vec<(String, NbtTag)>),
+
+    /// Tag type 11
+    IntArray(Vec<i32>),
+
+    /// Tag type 12
+    LongArray(Vec<i64>),
+}
+
+impl NbtTag {
+    /// Gets the tag type written before the tag.
+    pub fn tag_type(&self) -> u8 {
+        match self {
+            NbtTag::Byte(_) => 1,
+            NbtTag::Short(_) => 2,
+            NbtTag::Int(_) => 3,
+            NbtTag::Long(_) => 4,
+            NbtTag::Float(_) => 5,
+            NbtTag::Double(_) => 6,
+            NbtTag::ByteArray(_) => 7,
+            NbtTag::String(_) => 8,
+            NbtTag::List(_) => 9,
+            NbtTag::Compound(_) => 10,
+            NbtTag::IntArray(_) => 11,
+            NbtTag::LongArray(_) => 12,
+        }
+    }
+
+    /// Gets the entry of a compound with the given name.
+    pub fn get(&self, name: &str) -> Option<&NbtTag> {
+        self.as_compound()?.iter().find(|(key, _)| key == name).map(|(_, tag)| tag)
+    }
+
+    /// Gets the value of a byte, short, int or long tag.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            NbtTag::Byte(value) => Some(*value as i64),
+            NbtTag::Short(value) => Some(*value as i64),
+            NbtTag::Int(value) => Some(*value as i64),
+            NbtTag::Long(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value of a float or double tag.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            NbtTag::Float(value) => Some(*value as f64),
+            NbtTag::Double(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value of a string tag.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            NbtTag::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Gets the items of a list tag.
+    pub fn as_list(&self) -> Option<&[NbtTag]> {
+        match self {
+            NbtTag::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Gets the entries of a compound tag.
+    pub fn as_compound(&self) -> Option<&[(String, NbtTag)]> {
+        match self {
+            NbtTag::Compound(entries) => Some(entries),
+            _ => None,
+        }
+    }
+}
+
+//#region Modified UTF-8
+
+// Decodes Java's modified UTF-8, which writes NUL as two bytes and characters past U+FFFF as surrogate pairs
+fn decode_modified_utf8(mut bytes: Vec<u8>) -> Option<String> {
+    // Without NULs or 4-byte sequences, valid UTF-8 means the same in both encodings
+    if bytes.iter().all(|byte| *byte != 0 && *byte < 0xF0) {
+        match String::from_utf8(bytes) {
+            Ok(string) => return Some(string),
+            Err(error) => bytes = error.into_bytes(),
+        }
+    }
+    let continuation = |byte: Option<&u8>| byte.filter(|byte| *byte & 0xC0 == 0x80).map(|byte| (byte & 0x3F) as u16);
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        let (unit, length) = match byte {
+            0x01..=0x7F => (byte as u16, 1),
+            0xC0..=0xDF => (((byte & 0x1F) as u16) << 6 | continuation(bytes.get(i + 1))?, 2),
+            0xE0..=0xEF => {
+                let high = continuation(bytes.get(i + 1))?;
+                (((byte & 0x0F) as u16) << 12 | high << 6 | continuation(bytes.get(i + 2))?, 3)
+            }
+            _ => return None,
+        };
+        units.push(unit);
+        i += length;
+    }
+    String::from_utf16(&units).ok()
+}
+
+// Encodes Java's modified UTF-8, borrowing strings that are the same as in UTF-8
+fn encode_modified_utf8(string: &str) -> Cow<'_, [u8]> {
+    if string.bytes().all(|byte| byte != 0 && byte < 0xF0) {
+        return Cow::Borrowed(string.as_bytes());
+    }
+    let mut bytes = Vec::with_capacity(string.len() + 8);
+    for unit in string.encode_utf16() {
+        match unit {
+            0x01..=0x7F => bytes.push(unit as u8),
+            0x00 | 0x80..=0x7FF => bytes.extend_from_slice(&[0xC0 | (unit >> 6) as u8, 0x80 | (unit & 0x3F) as u8]),
+            _ => bytes.extend_from_slice(&[
+                0xE0 | (unit >> 12) as u8,
+                0x80 | ((unit >> 6) & 0x3F) as u8,
+                0x80 | (unit & 0x3F) as u8,
+            ]),
+        }
+    }
+    Cow::Owned(bytes)
+}
+
+//#endregion Modified UTF-8
+
+//#region NBT files
+
+/// Reads a named NBT tree from a file, decompressing it first if it is gzipped, as Java's `level.dat` is.
+#[cfg(feature = "deflate")]
+pub fn read_nbt_file<P: AsRef<Path>>(path: P, format: NbtFormat) -> io::Result<(String, NbtTag)> {
+    Buffer::from_file(path)?
+        .read_nbt_gzip(format)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid NBT file"))
+}
+
+/// Writes a named NBT tree to a gzipped file, replacing its contents.
+#[cfg(feature = "deflate")]
+pub fn write_nbt_file<P: AsRef<Path>>(path: P, format: NbtFormat, name: &str, tag: &NbtTag) -> io::Result<()> {
+    let mut buffer = Buffer::new();
+    buffer
+        .write_nbt_gzip(format, name, tag)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "NBT tree can't be encoded"))?;
+    buffer.write_to_file(path)
+}
+
+//#endregion NBT files
+
+impl<S: Storage> Buffer<S> {
+
+    //#region NBT reading methods
+
+    /// Reads a named NBT tree, the root tag of files and of older network protocols.
+    /// Fails if the tree is malformed or goes past the decode limits.
+    pub fn read_nbt(&mut self, format: NbtFormat) -> Option<(String, NbtTag)> {
+        self.atomic(|buffer| buffer.with_byte_order(format.byte_order(), |buffer| {
+            let tag_type = buffer.read_u8()?;
+            let name = buffer.read_nbt_string(format)?;
+            Some((name, buffer.read_nbt_payload(tag_type, format)?))
+        }))
+    }
+
+    /// Reads a nameless NBT tree, the root tag sent by the Java network protocol since 1.20.2.
+    pub fn read_nbt_nameless(&mut self, format: NbtFormat) -> Option<NbtTag> {
+        self.atomic(|buffer| buffer.with_byte_order(format.byte_order(), |buffer| {
+            let tag_type = buffer.read_u8()?;
+            buffer.read_nbt_payload(tag_type, format)
+        }))
+    }
+
+    /// Reads a named NBT tree from the rest of the buffer, decompressing it first if it is gzipped.
+    /// The decompressed size counts against the `max_bytes_length` and `max_total_allocation` limits.
+    #[cfg(feature = "deflate")]
+    pub fn read_nbt_gzip(&mut self, format: NbtFormat) -> Option<(String, NbtTag)> {
+        self.atomic(|buffer| {
+            if buffer.peek_bytes(2) != Some(&[0x1F, 0x8B][..]) {
+                return buffer.read_nbt(format);
+            }
+            let mut decompressed = buffer.read_compressed(buffer.remaining(), Codec::Gzip)?;
+            let tree = decompressed.read_nbt(format);
+            buffer.allocated = decompressed.allocated;
+            tree
+        })
+    }
+
+    // Reads the payload of a tag of the given type
+    fn read_nbt_payload(&mut self, tag_type: u8, format: NbtFormat) -> Option<NbtTag> {
+        let tag = match tag_type {
+            1 => NbtTag::Byte(self.read_i8()?),
+            2 => NbtTag::Short(self.read_i16()?),
+            3 => NbtTag::Int(self.read_nbt_int(format)?),
+            4 => NbtTag::Long(self.read_nbt_long(format)?),
+            5 => NbtTag::Float(self.read_f32()?),
+            6 => NbtTag::Double(self.read_f64()?),
+            7 => {
+                let length = self.read_nbt_length(format)?;
+                NbtTag::ByteArray(self.read_i8_vec(length)?)
+            }
+            8 => NbtTag::String(self.read_nbt_string(format)?),
+            9 => {
+                self.enter_nested()?;
+                let items = self.read_nbt_list(format);
+                self.exit_nested();
+                NbtTag::List(items?)
+            }
+            10 => {
+                self.enter_nested()?;
+                let entries = self.read_nbt_compound(format);
+                self.exit_nested();
+                NbtTag::Compound(entries?)
+            }
+            11 => {
+                let length = self.read_nbt_length(format)?;
+                NbtTag::IntArray(match format {
+                    NbtFormat::BedrockNetwork => self.read_nbt_items(length, |buffer| buffer.read_sint32())?,
+                    _ => self.read_i32_vec(length)?,
+                })
+            }
+            12 => {
+                let length = self.read_nbt_length(format)?;
+                NbtTag::LongArray(match format {
+                    NbtFormat::BedrockNetwork => self.read_nbt_items(length, |buffer| buffer.read_sint64())?,
+                    _ => self.read_i64_vec(length)?,
+                })
+            }
+            _ => return None,
+        };
+        Some(tag)
+    }
+
+    // Reads the item type, length and items of a list
+    fn read_nbt_list(&mut self, format: NbtFormat) -> Option<Vec<NbtTag>> {
+        let item_type = self.read_u8()?;
+        let length = self.read_nbt_length(format)?;
+        // Only an empty list may have the end tag type
+        if item_type == 0 && length > 0 {
+            return None;
+        }
+        self.read_nbt_items(length, |buffer| buffer.read_nbt_payload(item_type, format))
+    }
+
+    // Reads the entries of a compound up to its end tag
+    fn read_nbt_compound(&mut self, format: NbtFormat) -> Option<Vec<(String, NbtTag)>> {
+        let mut entries = Vec::new();
+        loop {
+            let tag_type = self.read_u8()?;
+            if tag_type == 0 {
+                return Some(entries);
+            }
+            self.check_collection_count(entries.len() + 1)?;
+            self.allocate(core::mem::size_of::<(String, NbtTag)>())?;
+            let name = self.read_nbt_string(format)?;
+            entries.push((name, self.read_nbt_payload(tag_type, format)?));
+        }
+    }
+
+    // Reads a known number of items one at a time, counting them against the decode limits
+    fn read_nbt_items<T>(&mut self, length: usize, mut read: impl FnMut(&mut Self) -> Option<T>) -> Option<Vec<T>> {
+        self.check_collection_count(length)?;
+        let mut items = Vec::new();
+        for _ in 0..length {
+            self.allocate(core::mem::size_of::<T>())?;
+            items.push(read(self)?);
+        }
+        Some(items)
+    }
+
+    // Reads a string, prefixed with its length in bytes
+    fn read_nbt_string(&mut self, format: NbtFormat) -> Option<String> {
+        let length = match format {
+            NbtFormat::BedrockNetwork => self.read_mc_varint()? as u32 as usize,
+            _ => self.read_u16()? as usize,
+        };
+        let bytes = self.read_limited_bytes(length, self.decode_limits.max_string_length)?;
+        match format {
+            NbtFormat::Java => decode_modified_utf8(bytes),
+            _ => String::from_utf8(bytes).ok(),
+        }
+    }
+
+    // Reads the length of a list or array, failing if it is negative
+    fn read_nbt_length(&mut self, format: NbtFormat) -> Option<usize> {
+        usize::try_from(self.read_nbt_int(format)?).ok()
+    }
+
+    fn read_nbt_int(&mut self, format: NbtFormat) -> Option<i32> {
+        match format {
+            NbtFormat::BedrockNetwork => self.read_sint32(),
+            _ => self.read_i32(),
+        }
+    }
+
+    fn read_nbt_long(&mut self, format: NbtFormat) -> Option<i64> {
+        match format {
+            NbtFormat::BedrockNetwork => self.read_sint64(),
+            _ => self.read_i64(),
+        }
+    }
+
+    //#endregion NBT reading methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region NBT writing methods
+
+    /// Writes a named NBT tree, the root tag of files and of older network protocols.
+    /// Fails if a list mixes tag types or a length doesn't fit its prefix, and then writes nothing.
+    pub fn write_nbt(&mut self, format: NbtFormat, name: &str, tag: &NbtTag) -> Option<()> {
+        let mut encoded = Buffer::new();
+        encoded.byte_order = format.byte_order();
+        encoded.write_u8(tag.tag_type())?;
+        encoded.write_nbt_string(format, name)?;
+        encoded.write_nbt_payload(format, tag)?;
+        self.write_bytes(encoded.as_slice())
+    }
+
+    /// Writes a nameless NBT tree, the root tag sent by the Java network protocol since 1.20.2.
+    pub fn write_nbt_nameless(&mut self, format: NbtFormat, tag: &NbtTag) -> Option<()> {
+        let mut encoded = Buffer::new();
+        encoded.byte_order = format.byte_order();
+        encoded.write_u8(tag.tag_type())?;
+        encoded.write_nbt_payload(format, tag)?;
+        self.write_bytes(encoded.as_slice())
+    }
+
+    /// Writes a named NBT tree compressed with gzip, the way Java stores NBT files.
+    #[cfg(feature = "deflate")]
+    pub fn write_nbt_gzip(&mut self, format: NbtFormat, name: &str, tag: &NbtTag) -> Option<()> {
+        let mut encoded = Buffer::new();
+        encoded.write_nbt(format, name, tag)?;
+        self.write_bytes(&Codec::Gzip.compress(encoded.as_slice())?)
+    }
+
+    // The methods below can fail halfway, so they are only used on temporary buffers
+
+    // Writes the payload of a tag
+    fn write_nbt_payload(&mut self, format: NbtFormat, tag: &NbtTag) -> Option<()> {
+        match tag {
+            NbtTag::Byte(value) => self.write_i8(*value),
+            NbtTag::Short(value) => self.write_i16(*value),
+            NbtTag::Int(value) => self.write_nbt_int(format, *value),
+            NbtTag::Long(value) => self.write_nbt_long(format, *value),
+            NbtTag::Float(value) => self.write_f32(*value),
+            NbtTag::Double(value) => self.write_f64(*value),
+            NbtTag::ByteArray(bytes) => {
+                self.write_nbt_length(format, bytes.len())?;
+                self.write_i8_slice(bytes)
+            }
+            NbtTag::String(string) => self.write_nbt_string(format, string),
+            NbtTag::List(items) => {
+                let item_type = items.first().map_or(0, NbtTag::tag_type);
+                if items.iter().any(|item| item.tag_type() != item_type) {
+                    return None;
+                }
+                self.write_u8(item_type)?;
+                self.write_nbt_length(format, items.len())?;
+                items.iter().try_for_each(|item| self.write_nbt_payload(format, item))
+            }
+            NbtTag::Compound(entries) => {
+                for (name, tag) in entries {
+                    self.write_u8(tag.tag_type())?;
+                    self.write_nbt_string(format, name)?;
+                    self.write_nbt_payload(format, tag)?;
+                }
+                self.write_u8(0)
+            }
+            NbtTag::IntArray(values) => {
+                self.write_nbt_length(format, values.len())?;
+                match format {
+                    NbtFormat::BedrockNetwork => values.iter().try_for_each(|value| self.write_sint32(*value)),
+                    _ => self.write_i32_slice(values),
+                }
+            }
+            NbtTag::LongArray(values) => {
+                self.write_nbt_length(format, values.len())?;
+                match format {
+                    NbtFormat::BedrockNetwork => values.iter().try_for_each(|value| self.write_sint64(*value)),
+                    _ => self.write_i64_slice(values),
+                }
+            }
+        }
+    }
+
+    // Writes a string, prefixed with its length in bytes
+    fn write_nbt_string(&mut self, format: NbtFormat, string: &str) -> Option<()> {
+        let bytes = match format {
+            NbtFormat::Java => encode_modified_utf8(string),
+            _ => Cow::Borrowed(string.as_bytes()),
+        };
+        match format {
+            NbtFormat::BedrockNetwork => self.write_mc_varint(u32::try_from(bytes.len()).ok()? as i32)?,
+            _ => self.write_u16(u16::try_from(bytes.len()).ok()?)?,
+        }
+        self.write_bytes(&bytes)
+    }
+
+    // Writes the length of a list or array
+    fn write_nbt_length(&mut self, format: NbtFormat, length: usize) -> Option<()> {
+        self.write_nbt_int(format, i32::try_from(length).ok()?)
+    }
+
+    fn write_nbt_int(&mut self, format: NbtFormat, value: i32) -> Option<()> {
+        match format {
+            NbtFormat::BedrockNetwork => self.write_sint32(value),
+            _ => self.write_i32(value),
+        }
+    }
+
+    fn write_nbt_long(&mut self, format: NbtFormat, value: i64) -> Option<()> {
+        match format {
+            NbtFormat::BedrockNetwork => self.write_sint64(value),
+            _ => self.write_i64(value),
+        }
+    }
+
+    //#endregion NBT writing methods
+}
@@ -0,0 +1,207 @@
+use crate::Buffer::Buffer;
+use crate::ByteOrder::ByteOrder;
+use crate::Storage::{Storage, StorageMut};
+
+//#region Conversions
+
+/// Converts the bits of an IEEE 754 half-precision number to an `f32`. Every half is exactly representable.
+pub fn f16_to_f32(half: u16) -> f32 {
+    let sign = ((half & 0x8000) as u32) << 16;
+    let exponent = ((half >> 10) & 0x1F) as u32;
+    let mantissa = (half & 0x3FF) as u32;
+    match exponent {
+        0 if mantissa == 0 => f32::from_bits(sign),
+        0 => {
+            // Subnormal, the mantissa counts units of 2^-24
+            let value = mantissa as f32 / 16_777_216.0;
+            if sign != 0 { -value } else { value }
+        }
+        0x1F => f32::from_bits(sign | 0x7F80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+    }
+}
+
+/// Converts an `f32` to the bits of an IEEE 754 half-precision number.
+/// Rounds to the nearest half, ties to even. Values too large for a half become infinity.
+pub fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xFF) as i32;
+    let mantissa = bits & 0x7F_FFFF;
+
+    if exponent == 0xFF {
+        // Infinity stays infinity, NaN stays a quiet NaN
+        let nan = if mantissa != 0 { 0x0200 | (mantissa >> 13) as u16 } else { 0 };
+        return sign | 0x7C00 | nan;
+    }
+
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1F {
+        return sign | 0x7C00;
+    }
+    if exponent <= 0 {
+        // Subnormal or zero, shifting in the implicit leading bit
+        if exponent < -10 {
+            return sign;
+        }
+        let shift = (14 - exponent) as u32;
+        let half = round_shifted(mantissa | 0x80_0000, shift);
+        return sign | half as u16;
+    }
+
+    // A carry out of the mantissa correctly bumps the exponent, up to infinity
+    let half = round_shifted(((exponent as u32) << 23) | mantissa, 13);
+    sign | half as u16
+}
+
+/// Converts the bits of a bfloat16 number to an `f32`.
+pub fn bf16_to_f32(half: u16) -> f32 {
+    f32::from_bits((half as u32) << 16)
+}
+
+/// Converts an `f32` to the bits of a bfloat16 number, rounding to nearest, ties to even.
+pub fn f32_to_bf16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    if value.is_nan() {
+        return ((bits >> 16) | 0x0040) as u16;
+    }
+    round_shifted(bits, 16) as u16
+}
+
+// Shifts right, rounding to nearest, ties to even
+fn round_shifted(value: u32, shift: u32) -> u32 {
+    let shifted = value >> shift;
+    let remainder = value & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    if remainder > halfway || (remainder == halfway && shifted & 1 == 1) {
+        shifted + 1
+    } else {
+        shifted
+    }
+}
+
+//#endregion Conversions
+
+impl<S: Storage> Buffer<S> {
+
+    //#region Half-precision reading methods
+
+    /// Reads an IEEE 754 half-precision floating-point number from the buffer.
+    pub fn read_f16(&mut self) -> Option<f32> {
+        self.read_u16().map(f16_to_f32)
+    }
+
+    /// Reads a bfloat16 floating-point number from the buffer.
+    pub fn read_bf16(&mut self) -> Option<f32> {
+        self.read_u16().map(bf16_to_f32)
+    }
+
+    //#endregion Half-precision reading methods
+
+    //#region Fixed-point reading methods
+
+    /// Reads a signed fixed-point number with `INT` integer bits, including the sign, and `FRAC` fractional bits.
+    /// `INT + FRAC` must be 8, 16, 24, 32, 40, 48, 56 or 64, e.g. `read_fixed::<16, 16>()` reads a Q16.16 number.
+    pub fn read_fixed<const INT: u32, const FRAC: u32>(&mut self) -> Option<f64> {
+        let bits = const { fixed_width(INT, FRAC) };
+        let raw = self.read_fixed_raw(bits / 8)?;
+        // Sign-extends the raw value
+        let raw = ((raw << (64 - bits)) as i64) >> (64 - bits);
+        Some(raw as f64 / fixed_scale(FRAC))
+    }
+
+    /// Reads an unsigned fixed-point number with `INT` integer bits and `FRAC` fractional bits.
+    pub fn read_ufixed<const INT: u32, const FRAC: u32>(&mut self) -> Option<f64> {
+        let bits = const { fixed_width(INT, FRAC) };
+        let raw = self.read_fixed_raw(bits / 8)?;
+        Some(raw as f64 / fixed_scale(FRAC))
+    }
+
+    // Reads an unsigned integer of n bytes in the buffer's byte order
+    fn read_fixed_raw(&mut self, n: u32) -> Option<u64> {
+        let bytes = self.peek_bytes(n as usize)?;
+        let raw = match self.byte_order {
+            ByteOrder::BigEndian => bytes.iter().fold(0, |raw, byte| (raw << 8) | *byte as u64),
+            ByteOrder::LittleEndian => bytes.iter().rev().fold(0, |raw, byte| (raw << 8) | *byte as u64),
+        };
+        self.position += n as usize;
+        Some(raw)
+    }
+
+    //#endregion Fixed-point reading methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region Half-precision writing methods
+
+    /// Writes an `f32` as an IEEE 754 half-precision floating-point number, rounding to the nearest half.
+    pub fn write_f16(&mut self, value: f32) -> Option<()> {
+        self.write_u16(f32_to_f16(value))
+    }
+
+    /// Writes an `f32` as a bfloat16 floating-point number, rounding to the nearest bfloat16.
+    pub fn write_bf16(&mut self, value: f32) -> Option<()> {
+        self.write_u16(f32_to_bf16(value))
+    }
+
+    //#endregion Half-precision writing methods
+
+    //#region Fixed-point writing methods
+
+    /// Writes a signed fixed-point number with `INT` integer bits, including the sign, and `FRAC` fractional bits.
+    /// Rounds to the nearest representable value. Fails if the value is out of range or NaN.
+    pub fn write_fixed<const INT: u32, const FRAC: u32>(&mut self, value: f64) -> Option<()> {
+        let bits = const { fixed_width(INT, FRAC) };
+        let raw = round_fixed(value, FRAC)?;
+        let limit = 1i128 << (bits - 1);
+        if raw < -limit || raw >= limit {
+            return None;
+        }
+        self.write_fixed_raw(raw as u64, bits / 8)
+    }
+
+    /// Writes an unsigned fixed-point number with `INT` integer bits and `FRAC` fractional bits.
+    /// Rounds to the nearest representable value. Fails if the value is out of range or NaN.
+    pub fn write_ufixed<const INT: u32, const FRAC: u32>(&mut self, value: f64) -> Option<()> {
+        let bits = const { fixed_width(INT, FRAC) };
+        let raw = round_fixed(value, FRAC)?;
+        if raw < 0 || raw >= 1i128 << bits {
+            return None;
+        }
+        self.write_fixed_raw(raw as u64, bits / 8)
+    }
+
+    // Writes the low n bytes of an integer in the buffer's byte order
+    fn write_fixed_raw(&mut self, raw: u64, n: u32) -> Option<()> {
+        let n = n as usize;
+        match self.byte_order {
+            ByteOrder::BigEndian => self.write_bytes(&raw.to_be_bytes()[8 - n..]),
+            ByteOrder::LittleEndian => self.write_bytes(&raw.to_le_bytes()[..n]),
+        }
+    }
+
+    //#endregion Fixed-point writing methods
+}
+
+// Checks the width of a fixed-point type at compile time, returning its number of bits
+const fn fixed_width(int: u32, frac: u32) -> u32 {
+    let bits = int + frac;
+    assert!(bits > 0 && bits <= 64 && bits.is_multiple_of(8), "fixed-point numbers must be a whole number of bytes, up to 64 bits");
+    bits
+}
+
+// The value of one unit of the raw integer
+fn fixed_scale(frac: u32) -> f64 {
+    (1u128 << frac) as f64
+}
+
+// Scales and rounds a value to its raw integer, half away from zero
+fn round_fixed(value: f64, frac: u32) -> Option<i128> {
+    if value.is_nan() {
+        return None;
+    }
+    let scaled = value * fixed_scale(frac);
+    let rounded = if scaled >= 0.0 { scaled + 0.5 } else { scaled - 0.5 };
+    Some(rounded as i128)
+}
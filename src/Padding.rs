@@ -0,0 +1,72 @@
+use crate::Buffer::Buffer;
+use crate::Storage::{Storage, StorageMut};
+
+// The number of bytes from an offset to the next multiple of n past the alignment base
+// Fails if n is 0, or the offset is before the base.
+fn padding_to(offset: usize, base: usize, n: usize) -> Option<usize> {
+    let remainder = offset.checked_sub(base)?.checked_rem(n)?;
+    Some(if remainder == 0 { 0 } else { n - remainder })
+}
+
+impl<S: Storage> Buffer<S> {
+
+    //#region Padding reading methods
+
+    /// Moves the position forward to the next multiple of n bytes past the alignment base.
+    /// Fails without moving if n is 0, the position is before the base, or the padding is past the end of the buffer.
+    pub fn align_to(&mut self, n: usize) -> Option<()> {
+        let padding = padding_to(self.position, self.alignment_base, n)?;
+        self.take_slice(padding).map(|_| ())
+    }
+
+    /// Skips n bytes of padding or reserved space, whatever they hold.
+    pub fn skip_padding(&mut self, n: usize) -> Option<()> {
+        self.take_slice(n).map(|_| ())
+    }
+
+    /// Skips n bytes of padding or reserved space, failing without moving unless they are all zero.
+    pub fn skip_padding_strict(&mut self, n: usize) -> Option<()> {
+        if !self.peek_bytes(n)?.iter().all(|byte| *byte == 0) {
+            return None;
+        }
+        self.skip_padding(n)
+    }
+
+    //#endregion Padding reading methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region Padding writing methods
+
+    /// Writes zeros until the length is a multiple of n bytes past the alignment base.
+    /// Fails without writing if n is 0, the length is before the base, or the padding doesn't fit.
+    pub fn align_write_to(&mut self, n: usize) -> Option<()> {
+        let padding = padding_to(self.len(), self.alignment_base, n)?;
+        self.write_zeros(padding)
+    }
+
+    /// Writes n copies of the fill byte.
+    /// Fails without writing if they don't fit.
+    pub fn write_padding(&mut self, n: usize, fill: u8) -> Option<()> {
+        if !self.has_space(n) {
+            return None;
+        }
+        // Written through a stack chunk, so large paddings don't allocate
+        let chunk = [fill; 256];
+        let mut remaining = n;
+        while remaining > 0 {
+            let count = remaining.min(chunk.len());
+            self.write_bytes(&chunk[..count])?;
+            remaining -= count;
+        }
+        Some(())
+    }
+
+    /// Writes n zero bytes, e.g. for a reserved field.
+    pub fn write_zeros(&mut self, n: usize) -> Option<()> {
+        self.write_padding(n, 0)
+    }
+
+    //#endregion Padding writing methods
+}
@@ -0,0 +1,163 @@
+use core::fmt;
+use core::mem::{align_of, size_of};
+
+use crate::Buffer::Buffer;
+use crate::Storage::{Storage, StorageMut};
+
+/// Plain old data: a type that can be copied to and from its raw bytes.
+///
+/// Plain integer and float fields are in native byte order. Use the endian wrapper types, such as `U32Be`,
+/// for layouts that must read the same on every platform. They also have an alignment of 1,
+/// so a struct made only of them can always be viewed in place.
+///
+/// # Safety
+///
+/// Implementors must be `#[repr(C)]` or `#[repr(transparent)]`, have no padding bytes,
+/// and be made only of `Pod` fields, so that every bit pattern is a valid value.
+///
+/// ```rust
+/// use muscleman::Buffer::Buffer;
+/// use muscleman::Pod::{Pod, U16Be, U32Le};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy)]
+/// struct Header {
+///     magic: [u8; 2],
+///     version: U16Be,
+///     length: U32Le,
+/// }
+///
+/// // SAFETY: repr(C), no padding, and every field is Pod
+/// unsafe impl Pod for Header {}
+///
+/// let mut buffer = Buffer::wrap(vec![b'M', b'M', 0x00, 0x02, 0x10, 0x00, 0x00, 0x00]);
+/// let header = buffer.view::<Header>().unwrap();
+/// assert_eq!((header.version.get(), header.length.get()), (2, 16));
+/// ```
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($ty:ty),*) => {$(
+        // SAFETY: primitive integers and floats have no padding, and every bit pattern is valid
+        unsafe impl Pod for $ty {}
+    )*};
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+// SAFETY: arrays have no padding between their items
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+// Gets the raw bytes of a value
+fn pod_bytes<T: Pod>(value: &T) -> &[u8] {
+    // SAFETY: T has no padding, so every one of its bytes is initialized
+    unsafe { core::slice::from_raw_parts((value as *const T).cast::<u8>(), size_of::<T>()) }
+}
+
+//#region Endian wrappers
+
+macro_rules! endian_wrappers {
+    ($($name:ident($ty:ty, $from_bytes:ident, $to_bytes:ident): $doc:literal;)*) => {$(
+        #[doc = $doc]
+        #[repr(transparent)]
+        #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+        pub struct $name([u8; size_of::<$ty>()]);
+
+        impl $name {
+            /// Wraps a value.
+            pub const fn new(value: $ty) -> $name {
+                $name(value.$to_bytes())
+            }
+
+            /// Gets the value.
+            pub const fn get(self) -> $ty {
+                <$ty>::$from_bytes(self.0)
+            }
+
+            /// Replaces the value.
+            pub fn set(&mut self, value: $ty) {
+                self.0 = value.$to_bytes();
+            }
+        }
+
+        // SAFETY: a byte array with an alignment of 1
+        unsafe impl Pod for $name {}
+
+        impl From<$ty> for $name {
+            fn from(value: $ty) -> $name {
+                $name::new(value)
+            }
+        }
+
+        impl From<$name> for $ty {
+            fn from(value: $name) -> $ty {
+                value.get()
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&self.get(), f)
+            }
+        }
+    )*};
+}
+
+endian_wrappers! {
+    U16Be(u16, from_be_bytes, to_be_bytes): "A big-endian u16 with an alignment of 1.";
+    U16Le(u16, from_le_bytes, to_le_bytes): "A little-endian u16 with an alignment of 1.";
+    U32Be(u32, from_be_bytes, to_be_bytes): "A big-endian u32 with an alignment of 1.";
+    U32Le(u32, from_le_bytes, to_le_bytes): "A little-endian u32 with an alignment of 1.";
+    U64Be(u64, from_be_bytes, to_be_bytes): "A big-endian u64 with an alignment of 1.";
+    U64Le(u64, from_le_bytes, to_le_bytes): "A little-endian u64 with an alignment of 1.";
+    I16Be(i16, from_be_bytes, to_be_bytes): "A big-endian i16 with an alignment of 1.";
+    I16Le(i16, from_le_bytes, to_le_bytes): "A little-endian i16 with an alignment of 1.";
+    I32Be(i32, from_be_bytes, to_be_bytes): "A big-endian i32 with an alignment of 1.";
+    I32Le(i32, from_le_bytes, to_le_bytes): "A little-endian i32 with an alignment of 1.";
+    I64Be(i64, from_be_bytes, to_be_bytes): "A big-endian i64 with an alignment of 1.";
+    I64Le(i64, from_le_bytes, to_le_bytes): "A little-endian i64 with an alignment of 1.";
+    F32Be(f32, from_be_bytes, to_be_bytes): "A big-endian f32 with an alignment of 1.";
+    F32Le(f32, from_le_bytes, to_le_bytes): "A little-endian f32 with an alignment of 1.";
+    F64Be(f64, from_be_bytes, to_be_bytes): "A big-endian f64 with an alignment of 1.";
+    F64Le(f64, from_le_bytes, to_le_bytes): "A little-endian f64 with an alignment of 1.";
+}
+
+//#endregion Endian wrappers
+
+impl<S: Storage> Buffer<S> {
+
+    //#region Pod reading methods
+
+    /// Reads a plain-old-data value by copying its bytes, whatever their alignment.
+    pub fn read_pod<T: Pod>(&mut self) -> Option<T> {
+        let bytes = self.take_slice(size_of::<T>())?;
+        // SAFETY: there are enough bytes, any bit pattern is a valid T, and the read doesn't need alignment
+        Some(unsafe { bytes.as_ptr().cast::<T>().read_unaligned() })
+    }
+
+    /// Borrows the next bytes as a plain-old-data value, without copying them.
+    /// Fails without moving the position if there aren't enough bytes, or they aren't aligned for T.
+    pub fn view<T: Pod>(&mut self) -> Option<&T> {
+        let pointer = self.peek_bytes(size_of::<T>())?.as_ptr();
+        if pointer.align_offset(align_of::<T>()) != 0 {
+            return None;
+        }
+        self.position += size_of::<T>();
+        // SAFETY: the bytes are in bounds and aligned, any bit pattern is a valid T, and they live as long as the borrow of the buffer
+        Some(unsafe { &*pointer.cast::<T>() })
+    }
+
+    //#endregion Pod reading methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region Pod writing methods
+
+    /// Writes the raw bytes of a plain-old-data value.
+    pub fn write_pod<T: Pod>(&mut self, value: &T) -> Option<()> {
+        self.write_bytes(pod_bytes(value))
+    }
+
+    //#endregion Pod writing methods
+}
@@ -0,0 +1,318 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Buffer::Buffer;
+use crate::ByteOrder::ByteOrder;
+use crate::Storage::{Storage, StorageMut};
+
+/// The largest field number allowed by Protocol Buffers
+pub const MAX_FIELD_NUMBER: u32 = (1 << 29) - 1;
+
+/// The wire type of a Protocol Buffers field, stored in the low 3 bits of its tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WireType {
+    /// int32, int64, uint32, uint64, sint32, sint64, bool and enum
+    Varint = 0,
+
+    /// fixed64, sfixed64 and double
+    Fixed64 = 1,
+
+    /// string, bytes, submessages and packed repeated fields
+    LengthDelimited = 2,
+
+    /// Start of a deprecated group
+    StartGroup = 3,
+
+    /// End of a deprecated group
+    EndGroup = 4,
+
+    /// fixed32, sfixed32 and float
+    Fixed32 = 5,
+}
+
+impl WireType {
+    /// Gets the wire type with the given value, if there is one.
+    pub fn from_u8(value: u8) -> Option<WireType> {
+        match value {
+            0 => Some(WireType::Varint),
+            1 => Some(WireType::Fixed64),
+            2 => Some(WireType::LengthDelimited),
+            3 => Some(WireType::StartGroup),
+            4 => Some(WireType::EndGroup),
+            5 => Some(WireType::Fixed32),
+            _ => None,
+        }
+    }
+}
+
+impl<S: Storage> Buffer<S> {
+
+    //#region Protobuf reading methods
+
+    /// Reads a field tag, returning its field number and wire type.
+    /// Fails if the field number is 0 or too large, or the wire type is unknown.
+    pub fn read_tag(&mut self) -> Option<(u32, WireType)> {
+        self.atomic(|buffer| {
+            let key = buffer.read_varint()? as u64;
+            let field_number = u32::try_from(key >> 3).ok()?;
+            if field_number == 0 || field_number > MAX_FIELD_NUMBER {
+                return None;
+            }
+            Some((field_number, WireType::from_u8((key & 0x07) as u8)?))
+        })
+    }
+
+    /// Reads a ZigZag encoded sint32.
+    pub fn read_sint32(&mut self) -> Option<i32> {
+        self.atomic(|buffer| {
+            let value = u32::try_from(buffer.read_varint()? as u64).ok()?;
+            Some((value >> 1) as i32 ^ -((value & 1) as i32))
+        })
+    }
+
+    /// Reads a ZigZag encoded sint64.
+    pub fn read_sint64(&mut self) -> Option<i64> {
+        let value = self.read_varint()? as u64;
+        Some((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+
+    /// Reads a fixed32, which is always little-endian whatever the buffer's byte order.
+    pub fn read_fixed32(&mut self) -> Option<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    /// Reads a fixed64, which is always little-endian whatever the buffer's byte order.
+    pub fn read_fixed64(&mut self) -> Option<u64> {
+        self.take().map(u64::from_le_bytes)
+    }
+
+    /// Reads an sfixed32, which is always little-endian whatever the buffer's byte order.
+    pub fn read_sfixed32(&mut self) -> Option<i32> {
+        self.take().map(i32::from_le_bytes)
+    }
+
+    /// Reads an sfixed64, which is always little-endian whatever the buffer's byte order.
+    pub fn read_sfixed64(&mut self) -> Option<i64> {
+        self.take().map(i64::from_le_bytes)
+    }
+
+    /// Reads the bytes of a length-delimited field.
+    /// Fails if the length is larger than the `max_bytes_length` limit.
+    pub fn read_length_delimited(&mut self) -> Option<Vec<u8>> {
+        self.atomic(|buffer| {
+            let length = buffer.read_protobuf_length()?;
+            buffer.read_limited_bytes(length, buffer.decode_limits.max_bytes_length)
+        })
+    }
+
+    /// Reads a length-delimited UTF-8 string.
+    /// Fails if the length is larger than the `max_string_length` limit, or the string isn't valid UTF-8.
+    pub fn read_length_delimited_string(&mut self) -> Option<String> {
+        self.atomic(|buffer| {
+            let length = buffer.read_protobuf_length()?;
+            let bytes = buffer.read_limited_bytes(length, buffer.decode_limits.max_string_length)?;
+            String::from_utf8(bytes).ok()
+        })
+    }
+
+    /// Reads a length-delimited submessage as a buffer over its bytes, without copying them.
+    /// The submessage inherits the byte order and decode limits, and counts as one level of nesting.
+    /// Its length counts against this buffer's `max_total_allocation` limit, so reading many submessages adds up.
+    pub fn read_submessage(&mut self) -> Option<Buffer<&[u8]>> {
+        if self.depth >= self.decode_limits.max_nesting_depth {
+            return None;
+        }
+        let start = self.position;
+        let length = self.read_protobuf_length()?;
+        // Reads in the submessage are checked against the count from before it was charged
+        let (decode_limits, allocated, depth) = (self.decode_limits, self.allocated, self.depth + 1);
+        if self.allocate(length).is_none() {
+            self.position = start;
+            return None;
+        }
+        let byte_order = self.byte_order;
+        let mut submessage = Buffer::wrap(self.take_slice(length)?);
+        submessage.byte_order = byte_order;
+        submessage.decode_limits = decode_limits;
+        submessage.allocated = allocated;
+        submessage.depth = depth;
+        Some(submessage)
+    }
+
+    /// Reads a packed repeated field of varints.
+    /// Fails if the number of values is larger than the `max_collection_count` limit.
+    pub fn read_packed_varints(&mut self) -> Option<Vec<u64>> {
+        self.atomic(|buffer| {
+            let length = buffer.read_protobuf_length()?;
+            let end = buffer.position + length;
+            let mut values = Vec::new();
+            while buffer.position < end {
+                buffer.check_collection_count(values.len() + 1)?;
+                buffer.allocate(core::mem::size_of::<u64>())?;
+                values.push(buffer.read_varint()? as u64);
+            }
+            // The last varint can't run past the end of the field
+            if buffer.position != end {
+                return None;
+            }
+            Some(values)
+        })
+    }
+
+    /// Reads a packed repeated field of fixed32 values.
+    /// Fails if the number of values is larger than the `max_collection_count` limit.
+    pub fn read_packed_fixed32(&mut self) -> Option<Vec<u32>> {
+        self.atomic(|buffer| {
+            let length = buffer.read_protobuf_length()?;
+            if length % 4 != 0 {
+                return None;
+            }
+            buffer.with_byte_order(ByteOrder::LittleEndian, |buffer| buffer.read_u32_vec(length / 4))
+        })
+    }
+
+    /// Reads a packed repeated field of fixed64 values.
+    /// Fails if the number of values is larger than the `max_collection_count` limit.
+    pub fn read_packed_fixed64(&mut self) -> Option<Vec<u64>> {
+        self.atomic(|buffer| {
+            let length = buffer.read_protobuf_length()?;
+            if length % 8 != 0 {
+                return None;
+            }
+            buffer.with_byte_order(ByteOrder::LittleEndian, |buffer| buffer.read_u64_vec(length / 8))
+        })
+    }
+
+    /// Skips the value of a field with the given number and wire type, e.g. an unknown field.
+    /// Skipping a group skips every field up to its end, which must have the same field number.
+    /// Fails on a stray end of group.
+    pub fn skip_field(&mut self, field_number: u32, wire_type: WireType) -> Option<()> {
+        self.atomic(|buffer| {
+            match wire_type {
+                WireType::Varint => buffer.read_varint().map(|_| ()),
+                WireType::Fixed64 => buffer.take::<8>().map(|_| ()),
+                WireType::Fixed32 => buffer.take::<4>().map(|_| ()),
+                WireType::LengthDelimited => {
+                    let length = buffer.read_protobuf_length()?;
+                    buffer.take_slice(length).map(|_| ())
+                }
+                WireType::StartGroup => {
+                    buffer.enter_nested()?;
+                    let skipped = buffer.skip_group(field_number);
+                    buffer.exit_nested();
+                    skipped
+                }
+                WireType::EndGroup => None,
+            }
+        })
+    }
+
+    // Skips fields until the end of the group started with the given field number
+    fn skip_group(&mut self, field_number: u32) -> Option<()> {
+        loop {
+            match self.read_tag()? {
+                (end, WireType::EndGroup) => return (end == field_number).then_some(()),
+                (number, wire_type) => self.skip_field(number, wire_type)?,
+            }
+        }
+    }
+
+    // Reads the length of a length-delimited field, failing if it is past the end of the buffer
+    fn read_protobuf_length(&mut self) -> Option<usize> {
+        self.atomic(|buffer| {
+            let length = usize::try_from(buffer.read_varint()? as u64).ok()?;
+            if length > buffer.remaining() {
+                return None;
+            }
+            Some(length)
+        })
+    }
+
+    //#endregion Protobuf reading methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region Protobuf writing methods
+
+    /// Writes a field tag. Fails if the field number is 0 or too large.
+    pub fn write_tag(&mut self, field_number: u32, wire_type: WireType) -> Option<()> {
+        if field_number == 0 || field_number > MAX_FIELD_NUMBER {
+            return None;
+        }
+        self.write_varint(((field_number << 3) | wire_type as u32) as i64)
+    }
+
+    /// Writes a ZigZag encoded sint32.
+    pub fn write_sint32(&mut self, value: i32) -> Option<()> {
+        self.write_varint(((value << 1) ^ (value >> 31)) as u32 as i64)
+    }
+
+    /// Writes a ZigZag encoded sint64.
+    pub fn write_sint64(&mut self, value: i64) -> Option<()> {
+        self.write_varint((value << 1) ^ (value >> 63))
+    }
+
+    /// Writes a fixed32, which is always little-endian whatever the buffer's byte order.
+    pub fn write_fixed32(&mut self, value: u32) -> Option<()> {
+        self.write_bytes(&value.to_le_bytes())
+    }
+
+    /// Writes a fixed64, which is always little-endian whatever the buffer's byte order.
+    pub fn write_fixed64(&mut self, value: u64) -> Option<()> {
+        self.write_bytes(&value.to_le_bytes())
+    }
+
+    /// Writes an sfixed32, which is always little-endian whatever the buffer's byte order.
+    pub fn write_sfixed32(&mut self, value: i32) -> Option<()> {
+        self.write_bytes(&value.to_le_bytes())
+    }
+
+    /// Writes an sfixed64, which is always little-endian whatever the buffer's byte order.
+    pub fn write_sfixed64(&mut self, value: i64) -> Option<()> {
+        self.write_bytes(&value.to_le_bytes())
+    }
+
+    /// Writes bytes as a length-delimited field, prefixed with their length.
+    pub fn write_length_delimited(&mut self, bytes: &[u8]) -> Option<()> {
+        let mut prefix = Buffer::wrap_empty([0; 10]);
+        prefix.write_varint(bytes.len() as i64)?;
+        if !self.has_space(prefix.len().checked_add(bytes.len())?) {
+            return None;
+        }
+        self.write_bytes(prefix.as_slice())?;
+        self.write_bytes(bytes)
+    }
+
+    /// Writes a length-delimited submessage built by `build`.
+    /// The submessage is built in a separate buffer, since its length has to be written first.
+    pub fn write_submessage(&mut self, build: impl FnOnce(&mut Buffer) -> Option<()>) -> Option<()> {
+        let mut submessage = Buffer::new();
+        submessage.byte_order = self.byte_order;
+        build(&mut submessage)?;
+        self.write_length_delimited(submessage.as_slice())
+    }
+
+    /// Writes a packed repeated field of varints.
+    pub fn write_packed_varints(&mut self, values: &[u64]) -> Option<()> {
+        self.write_submessage(|packed| values.iter().try_for_each(|value| packed.write_varint(*value as i64)))
+    }
+
+    /// Writes a packed repeated field of fixed32 values.
+    pub fn write_packed_fixed32(&mut self, values: &[u32]) -> Option<()> {
+        self.write_submessage(|packed| {
+            packed.byte_order = ByteOrder::LittleEndian;
+            packed.write_u32_slice(values)
+        })
+    }
+
+    /// Writes a packed repeated field of fixed64 values.
+    pub fn write_packed_fixed64(&mut self, values: &[u64]) -> Option<()> {
+        self.write_submessage(|packed| {
+            packed.byte_order = ByteOrder::LittleEndian;
+            packed.write_u64_slice(values)
+        })
+    }
+
+    //#endregion Protobuf writing methods
+}
@@ -0,0 +1,731 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::Buffer::Buffer;
+use crate::Storage::{Storage, StorageMut};
+use crate::ByteOrder::ByteOrder;
+use crate::DecodeLimits::DecodeLimits;
+use crate::Value::Value;
+
+/// An error raised while parsing a schema, or while decoding or encoding a value with it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaError {
+    // The error message
+    message: String,
+
+    // The dotted path of the field the error happened in
+    path: String,
+}
+
+impl SchemaError {
+    // Default constructor
+    pub fn new(message: impl Into<String>) -> SchemaError {
+        SchemaError { message: message.into(), path: String::new() }
+    }
+
+    /// Gets the error message
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Gets the dotted path of the field the error happened in, such as `points.3.x`
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    // Prefixes the path with the field the error happened in
+    fn within(mut self, field: &str) -> SchemaError {
+        self.path = if self.path.is_empty() { field.to_string() } else { format!("{}.{}", field, self.path) };
+        self
+    }
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SchemaError {}
+
+/// Where the length of a string, byte array or array comes from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Length {
+    /// A fixed number of items
+    Fixed(usize),
+
+    /// An integer read right before the items
+    Prefix(Box<FieldType>),
+
+    /// The value of an earlier field in the same struct
+    Field(String),
+}
+
+/// The wire type of a schema field.
+/// Multi-byte numbers use the buffer's byte order when no order is given.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldType {
+    Bool,
+    U8,
+    I8,
+    U16(Option<ByteOrder>),
+    U32(Option<ByteOrder>),
+    U64(Option<ByteOrder>),
+    I16(Option<ByteOrder>),
+    I32(Option<ByteOrder>),
+    I64(Option<ByteOrder>),
+    F32(Option<ByteOrder>),
+    F64(Option<ByteOrder>),
+    VarInt,
+
+    /// A null-terminated string
+    CString,
+
+    /// A UTF-8 string
+    String(Length),
+    Bytes(Length),
+    Array(Box<FieldType>, Length),
+    Struct(Schema),
+}
+
+/// A named field of a schema.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    // The field's name
+    pub name: String,
+
+    // The field's wire type
+    pub field_type: FieldType,
+}
+
+/// A runtime description of a binary record, used to decode a buffer into a `Value` and back.
+///
+/// Schemas can be built in code or parsed from a text description with one field per line:
+///
+/// ```rust
+/// use muscleman::Schema::Schema;
+///
+/// let schema = Schema::parse("
+///     id: u16le
+///     name: string(prefix=u8)
+///     count: varint
+///     points: array(struct { x: i32; y: i32 }, count=count)
+/// ").unwrap();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Schema {
+    // The schema's fields, in wire order
+    pub fields: Vec<Field>,
+}
+
+impl Schema {
+
+    //#region Constructors
+
+    // Default constructor
+    pub fn new() -> Schema {
+        Schema { fields: Vec::new() }
+    }
+
+    /// Adds a field to the end of the schema.
+    pub fn field(mut self, name: &str, field_type: FieldType) -> Schema {
+        self.fields.push(Field { name: name.to_string(), field_type });
+        self
+    }
+
+    /// Parses a schema from its text description.
+    pub fn parse(text: &str) -> Result<Schema, SchemaError> {
+        let mut parser = Parser { tokens: tokenize(text)?, position: 0, depth: 0 };
+        parser.parse_fields(None)
+    }
+
+    /// Parses a schema from a JSON description.
+    /// The document is either an array of fields or an object with a `fields` array.
+    /// Each field is an object with a `name` and a `type`, plus the type's arguments.
+    ///
+    /// ```rust
+    /// use muscleman::Schema::Schema;
+    ///
+    /// let schema = Schema::from_json(r#"[
+    ///     { "name": "id", "type": "u16le" },
+    ///     { "name": "name", "type": "string", "prefix": "u8" }
+    /// ]"#).unwrap();
+    /// ```
+    pub fn from_json(text: &str) -> Result<Schema, SchemaError> {
+        schema_from_value(&Value::from_json(text)?)
+    }
+
+    //#endregion Constructors
+
+    //#region Decoding and encoding methods
+
+    /// Decodes a struct value from the buffer.
+    /// Fails without moving the buffer's position if any field can't be decoded.
+    pub fn decode<S: Storage>(&self, buffer: &mut Buffer<S>) -> Result<Value, SchemaError> {
+        decode_atomic(buffer, |buffer| {
+            let mut fields: Vec<(String, Value)> = Vec::with_capacity(self.fields.len());
+            for field in &self.fields {
+                let value = field.field_type.decode(buffer, &fields).map_err(|e| e.within(&field.name))?;
+                fields.push((field.name.clone(), value));
+            }
+            Ok(Value::Struct(fields))
+        })
+    }
+
+    /// Encodes a struct value into the buffer.
+    /// Fails if any field can't be encoded, and then writes nothing.
+    pub fn encode<S: StorageMut>(&self, value: &Value, buffer: &mut Buffer<S>) -> Result<(), SchemaError> {
+        let fields = match value {
+            Value::Struct(fields) => fields,
+            _ => return Err(SchemaError::new("expected a struct")),
+        };
+        // Fields are encoded with the buffer's settings into a temporary buffer, which can be dropped halfway
+        let mut encoded = Buffer::new();
+        encoded.byte_order = buffer.byte_order;
+        encoded.string_encoding = buffer.string_encoding;
+        encoded.string_terminator = buffer.string_terminator;
+        for field in &self.fields {
+            let value = lookup(fields, &field.name).ok_or_else(|| SchemaError::new(format!("missing field {}", field.name)))?;
+            field.field_type.encode(value, &mut encoded, fields).map_err(|e| e.within(&field.name))?;
+        }
+        buffer.write_bytes(encoded.as_slice()).ok_or_else(|| SchemaError::new("buffer is full"))
+    }
+
+    //#endregion Decoding and encoding methods
+}
+
+impl FieldType {
+
+    /// Parses a single type from its text description, such as `u16le` or `string(prefix=u8)`.
+    pub fn parse(text: &str) -> Result<FieldType, SchemaError> {
+        let mut parser = Parser { tokens: tokenize(text)?, position: 0, depth: 0 };
+        let field_type = parser.parse_type()?;
+        if parser.peek().is_some() {
+            return Err(SchemaError::new(format!("unexpected text after type {}", text)));
+        }
+        Ok(field_type)
+    }
+
+    /// Decodes a single value of this type from the buffer.
+    /// `scope` holds the fields decoded so far in the enclosing struct.
+    pub fn decode<S: Storage>(&self, buffer: &mut Buffer<S>, scope: &[(String, Value)]) -> Result<Value, SchemaError> {
+        decode_atomic(buffer, |buffer| {
+            let eof = || SchemaError::new("unexpected end of buffer");
+            let nested = || SchemaError::new("nesting depth exceeds the limit");
+            let value = match self {
+                FieldType::Bool => Value::Bool(buffer.read_boolean().ok_or_else(eof)?),
+                FieldType::U8 => Value::UInt(buffer.read_u8().ok_or_else(eof)?.into()),
+                FieldType::I8 => Value::Int(buffer.read_i8().ok_or_else(eof)?.into()),
+                FieldType::U16(order) => Value::UInt(buffer.with_byte_order(order.unwrap_or(buffer.byte_order), Buffer::read_u16).ok_or_else(eof)?.into()),
+                FieldType::U32(order) => Value::UInt(buffer.with_byte_order(order.unwrap_or(buffer.byte_order), Buffer::read_u32).ok_or_else(eof)?.into()),
+                FieldType::U64(order) => Value::UInt(buffer.with_byte_order(order.unwrap_or(buffer.byte_order), Buffer::read_u64).ok_or_else(eof)?),
+                FieldType::I16(order) => Value::Int(buffer.with_byte_order(order.unwrap_or(buffer.byte_order), Buffer::read_i16).ok_or_else(eof)?.into()),
+                FieldType::I32(order) => Value::Int(buffer.with_byte_order(order.unwrap_or(buffer.byte_order), Buffer::read_i32).ok_or_else(eof)?.into()),
+                FieldType::I64(order) => Value::Int(buffer.with_byte_order(order.unwrap_or(buffer.byte_order), Buffer::read_i64).ok_or_else(eof)?),
+                FieldType::F32(order) => Value::Float(buffer.with_byte_order(order.unwrap_or(buffer.byte_order), Buffer::read_f32).ok_or_else(eof)?.into()),
+                FieldType::F64(order) => Value::Float(buffer.with_byte_order(order.unwrap_or(buffer.byte_order), Buffer::read_f64).ok_or_else(eof)?),
+                FieldType::VarInt => Value::Int(buffer.read_varint().ok_or_else(eof)?),
+                FieldType::CString => Value::String(buffer.read_string().ok_or_else(eof)?),
+                FieldType::String(length) => {
+                    let length = length.decode(buffer, scope)?;
+                    let limits = buffer.get_decode_limits();
+                    if length > limits.max_string_length {
+                        return Err(SchemaError::new(format!("string length {} exceeds the limit of {}", length, limits.max_string_length)));
+                    }
+                    let bytes = buffer.read_limited_bytes(length, limits.max_string_length).ok_or_else(eof)?;
+                    Value::String(String::from_utf8(bytes).map_err(|_| SchemaError::new("invalid UTF-8 string"))?)
+                }
+                FieldType::Bytes(length) => {
+                    let length = length.decode(buffer, scope)?;
+                    let limits = buffer.get_decode_limits();
+                    if length > limits.max_bytes_length {
+                        return Err(SchemaError::new(format!("byte array length {} exceeds the limit of {}", length, limits.max_bytes_length)));
+                    }
+                    Value::Bytes(buffer.read_bytes(length).ok_or_else(eof)?)
+                }
+                FieldType::Array(item, length) => {
+                    let length = length.decode(buffer, scope)?;
+                    buffer.check_collection_count(length).ok_or_else(|| {
+                        SchemaError::new(format!("array count {} exceeds the limit of {}", length, buffer.get_decode_limits().max_collection_count))
+                    })?;
+                    buffer.enter_nested().ok_or_else(nested)?;
+                    let items = decode_items(item, length, buffer, scope);
+                    buffer.exit_nested();
+                    Value::Array(items?)
+                }
+                FieldType::Struct(schema) => {
+                    buffer.enter_nested().ok_or_else(nested)?;
+                    let value = schema.decode(buffer);
+                    buffer.exit_nested();
+                    value?
+                }
+            };
+            Ok(value)
+        })
+    }
+
+    /// Encodes a single value of this type into the buffer.
+    /// `scope` holds the fields of the enclosing struct.
+    pub fn encode<S: StorageMut>(&self, value: &Value, buffer: &mut Buffer<S>, scope: &[(String, Value)]) -> Result<(), SchemaError> {
+        let mismatch = || SchemaError::new(format!("value {} does not fit {}", value.to_json(), self.name()));
+        let written = match self {
+            FieldType::Bool => buffer.write_byte(value.as_bool().ok_or_else(mismatch)? as u8),
+            FieldType::U8 => buffer.write_u8(value.as_u64().and_then(|v| v.try_into().ok()).ok_or_else(mismatch)?),
+            FieldType::I8 => buffer.write_i8(value.as_i64().and_then(|v| v.try_into().ok()).ok_or_else(mismatch)?),
+            FieldType::U16(order) => {
+                let value: u16 = value.as_u64().and_then(|v| v.try_into().ok()).ok_or_else(mismatch)?;
+                buffer.with_byte_order(order.unwrap_or(buffer.byte_order), |buffer| buffer.write_u16(value))
+            }
+            FieldType::U32(order) => {
+                let value: u32 = value.as_u64().and_then(|v| v.try_into().ok()).ok_or_else(mismatch)?;
+                buffer.with_byte_order(order.unwrap_or(buffer.byte_order), |buffer| buffer.write_u32(value))
+            }
+            FieldType::U64(order) => {
+                let value = value.as_u64().ok_or_else(mismatch)?;
+                buffer.with_byte_order(order.unwrap_or(buffer.byte_order), |buffer| buffer.write_u64(value))
+            }
+            FieldType::I16(order) => {
+                let value: i16 = value.as_i64().and_then(|v| v.try_into().ok()).ok_or_else(mismatch)?;
+                buffer.with_byte_order(order.unwrap_or(buffer.byte_order), |buffer| buffer.write_i16(value))
+            }
+            FieldType::I32(order) => {
+                let value: i32 = value.as_i64().and_then(|v| v.try_into().ok()).ok_or_else(mismatch)?;
+                buffer.with_byte_order(order.unwrap_or(buffer.byte_order), |buffer| buffer.write_i32(value))
+            }
+            FieldType::I64(order) => {
+                let value = value.as_i64().ok_or_else(mismatch)?;
+                buffer.with_byte_order(order.unwrap_or(buffer.byte_order), |buffer| buffer.write_i64(value))
+            }
+            FieldType::F32(order) => {
+                let value = value.as_f64().ok_or_else(mismatch)? as f32;
+                buffer.with_byte_order(order.unwrap_or(buffer.byte_order), |buffer| buffer.write_f32(value))
+            }
+            FieldType::F64(order) => {
+                let value = value.as_f64().ok_or_else(mismatch)?;
+                buffer.with_byte_order(order.unwrap_or(buffer.byte_order), |buffer| buffer.write_f64(value))
+            }
+            FieldType::VarInt => buffer.write_varint(value.as_i64().ok_or_else(mismatch)?),
+            FieldType::CString => buffer.write_string(value.as_str().ok_or_else(mismatch)?),
+            FieldType::String(length) => {
+                let string = value.as_str().ok_or_else(mismatch)?;
+                length.encode(string.len(), buffer, scope)?;
+                buffer.write_bytes(string.as_bytes())
+            }
+            FieldType::Bytes(length) => {
+                let bytes = value.as_bytes().ok_or_else(mismatch)?;
+                length.encode(bytes.len(), buffer, scope)?;
+                buffer.write_bytes(&bytes)
+            }
+            FieldType::Array(item, length) => {
+                let items = match value {
+                    Value::Array(items) => items,
+                    _ => return Err(mismatch()),
+                };
+                length.encode(items.len(), buffer, scope)?;
+                for (i, value) in items.iter().enumerate() {
+                    item.encode(value, buffer, scope).map_err(|e| e.within(&i.to_string()))?;
+                }
+                Some(())
+            }
+            FieldType::Struct(schema) => {
+                schema.encode(value, buffer)?;
+                Some(())
+            }
+        };
+        written.ok_or_else(|| SchemaError::new("buffer is full"))
+    }
+
+    // Gets the name of the type, as used in text descriptions
+    fn name(&self) -> &'static str {
+        match self {
+            FieldType::Bool => "bool",
+            FieldType::U8 => "u8",
+            FieldType::I8 => "i8",
+            FieldType::U16(_) => "u16",
+            FieldType::U32(_) => "u32",
+            FieldType::U64(_) => "u64",
+            FieldType::I16(_) => "i16",
+            FieldType::I32(_) => "i32",
+            FieldType::I64(_) => "i64",
+            FieldType::F32(_) => "f32",
+            FieldType::F64(_) => "f64",
+            FieldType::VarInt => "varint",
+            FieldType::CString => "cstring",
+            FieldType::String(_) => "string",
+            FieldType::Bytes(_) => "bytes",
+            FieldType::Array(_, _) => "array",
+            FieldType::Struct(_) => "struct",
+        }
+    }
+}
+
+impl Length {
+
+    // Reads the length from the buffer or the scope
+    fn decode<S: Storage>(&self, buffer: &mut Buffer<S>, scope: &[(String, Value)]) -> Result<usize, SchemaError> {
+        let value = match self {
+            Length::Fixed(length) => return Ok(*length),
+            Length::Prefix(prefix) => prefix.decode(buffer, scope)?,
+            Length::Field(name) => lookup(scope, name).cloned().ok_or_else(|| SchemaError::new(format!("unknown length field {}", name)))?,
+        };
+        value.as_u64().and_then(|length| usize::try_from(length).ok()).ok_or_else(|| SchemaError::new("invalid length"))
+    }
+
+    // Writes the length prefix, or checks the length against the fixed length or the length field
+    fn encode<S: StorageMut>(&self, length: usize, buffer: &mut Buffer<S>, scope: &[(String, Value)]) -> Result<(), SchemaError> {
+        let expected = match self {
+            Length::Fixed(expected) => *expected as u64,
+            Length::Prefix(prefix) => return prefix.encode(&Value::UInt(length as u64), buffer, scope),
+            Length::Field(name) => lookup(scope, name).and_then(Value::as_u64).ok_or_else(|| SchemaError::new(format!("unknown length field {}", name)))?,
+        };
+        if expected != length as u64 {
+            return Err(SchemaError::new(format!("length {} does not match expected length {}", length, expected)));
+        }
+        Ok(())
+    }
+}
+
+// Decodes the items of an array, counting each item against the allocation limit
+fn decode_items<S: Storage>(item: &FieldType, length: usize, buffer: &mut Buffer<S>, scope: &[(String, Value)]) -> Result<Vec<Value>, SchemaError> {
+    let mut items = Vec::new();
+    for i in 0..length {
+        buffer.allocate(core::mem::size_of::<Value>()).ok_or_else(|| SchemaError::new("total allocation exceeds the limit"))?;
+        items.push(item.decode(buffer, scope).map_err(|e| e.within(&i.to_string()))?);
+    }
+    Ok(items)
+}
+
+// Runs a decode that either succeeds or leaves the buffer's position, allocation count and depth as they were
+fn decode_atomic<S: Storage, T>(buffer: &mut Buffer<S>, decode: impl FnOnce(&mut Buffer<S>) -> Result<T, SchemaError>) -> Result<T, SchemaError> {
+    let (position, allocated, depth) = (buffer.position, buffer.allocated, buffer.depth);
+    let result = decode(buffer);
+    if result.is_err() {
+        buffer.position = position;
+        buffer.allocated = allocated;
+        buffer.depth = depth;
+    }
+    result
+}
+
+// Finds the last field with the given name
+fn lookup<'a>(fields: &'a [(String, Value)], name: &str) -> Option<&'a Value> {
+    fields.iter().rev().find(|(field, _)| field == name).map(|(_, value)| value)
+}
+
+//#region Text description parser
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(usize),
+    Symbol(char),
+}
+
+// Splits a text description into tokens. Newlines are turned into ';' separators.
+fn tokenize(text: &str) -> Result<Vec<Token>, SchemaError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c == '#' {
+            while chars.peek().is_some_and(|&c| c != '\n') {
+                chars.next();
+            }
+        } else if c == '\n' {
+            chars.next();
+            tokens.push(Token::Symbol(';'));
+        } else if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphanumeric()) {
+                word.push(c);
+                chars.next();
+            }
+            let number = match word.strip_prefix("0x") {
+                Some(hex) => usize::from_str_radix(hex, 16),
+                None => word.parse(),
+            };
+            tokens.push(Token::Number(number.map_err(|_| SchemaError::new(format!("invalid number {}", word)))?));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_alphanumeric() || **c == '_') {
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Ident(word));
+        } else if ":(),={};".contains(c) {
+            chars.next();
+            tokens.push(Token::Symbol(c));
+        } else {
+            return Err(SchemaError::new(format!("unexpected character '{}'", c)));
+        }
+    }
+    Ok(tokens)
+}
+
+// A type argument, such as `u8` in `array(u8, count=4)` or `prefix=u8`
+enum Argument {
+    Type(FieldType),
+    Named(String, ArgumentValue),
+}
+
+enum ArgumentValue {
+    Number(usize),
+    Name(String),
+    Type(FieldType),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn skip_separators(&mut self) {
+        while self.peek() == Some(&Token::Symbol(';')) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), SchemaError> {
+        match self.next() {
+            Some(Token::Symbol(c)) if c == symbol => Ok(()),
+            token => Err(SchemaError::new(format!("expected '{}', found {:?}", symbol, token))),
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, SchemaError> {
+        match self.next() {
+            Some(Token::Ident(ident)) => Ok(ident),
+            token => Err(SchemaError::new(format!("expected a name, found {:?}", token))),
+        }
+    }
+
+    fn parse_fields(&mut self, closing: Option<char>) -> Result<Schema, SchemaError> {
+        let mut schema = Schema::new();
+        loop {
+            self.skip_separators();
+            match (self.peek(), closing) {
+                (None, None) => return Ok(schema),
+                (None, Some(c)) => return Err(SchemaError::new(format!("expected '{}'", c))),
+                (Some(Token::Symbol(c)), Some(closing)) if *c == closing => {
+                    self.position += 1;
+                    return Ok(schema);
+                }
+                _ => {}
+            }
+            let name = self.expect_ident()?;
+            self.expect(':')?;
+            let field_type = self.parse_type().map_err(|e| e.within(&name))?;
+            schema = schema.field(&name, field_type);
+            match self.peek() {
+                None | Some(Token::Symbol(';')) => {}
+                Some(Token::Symbol(c)) if Some(*c) == closing => {}
+                token => return Err(SchemaError::new(format!("expected a new line or ';', found {:?}", token))),
+            }
+        }
+    }
+
+    fn parse_type(&mut self) -> Result<FieldType, SchemaError> {
+        if self.depth >= DecodeLimits::DEFAULT_MAX_NESTING_DEPTH {
+            return Err(SchemaError::new("nesting depth exceeds the limit"));
+        }
+        self.depth += 1;
+        let field_type = self.parse_nested_type();
+        self.depth -= 1;
+        field_type
+    }
+
+    fn parse_nested_type(&mut self) -> Result<FieldType, SchemaError> {
+        let name = self.expect_ident()?;
+        if name == "struct" {
+            self.skip_separators();
+            self.expect('{')?;
+            return Ok(FieldType::Struct(self.parse_fields(Some('}'))?));
+        }
+        let mut arguments = Vec::new();
+        if self.peek() == Some(&Token::Symbol('(')) {
+            self.position += 1;
+            loop {
+                self.skip_separators();
+                if self.peek() == Some(&Token::Symbol(')')) {
+                    self.position += 1;
+                    break;
+                }
+                arguments.push(self.parse_argument()?);
+                self.skip_separators();
+                match self.next() {
+                    Some(Token::Symbol(',')) => {}
+                    Some(Token::Symbol(')')) => break,
+                    token => return Err(SchemaError::new(format!("expected ',' or ')', found {:?}", token))),
+                }
+            }
+        }
+        build_type(&name, arguments)
+    }
+
+    fn parse_argument(&mut self) -> Result<Argument, SchemaError> {
+        let is_named = matches!(
+            (self.tokens.get(self.position), self.tokens.get(self.position + 1)),
+            (Some(Token::Ident(_)), Some(Token::Symbol('=')))
+        );
+        if !is_named {
+            return Ok(Argument::Type(self.parse_type()?));
+        }
+        let key = self.expect_ident()?;
+        self.expect('=')?;
+        let value = match (key.as_str(), self.peek()) {
+            (_, Some(Token::Number(number))) => {
+                let number = *number;
+                self.position += 1;
+                ArgumentValue::Number(number)
+            }
+            ("count" | "len", _) => ArgumentValue::Name(self.expect_ident()?),
+            _ => ArgumentValue::Type(self.parse_type()?),
+        };
+        Ok(Argument::Named(key, value))
+    }
+}
+
+// Builds a type from its name and arguments
+fn build_type(name: &str, arguments: Vec<Argument>) -> Result<FieldType, SchemaError> {
+    let (order, base) = if let Some(base) = name.strip_suffix("le") {
+        (Some(ByteOrder::LittleEndian), base)
+    } else if let Some(base) = name.strip_suffix("be") {
+        (Some(ByteOrder::BigEndian), base)
+    } else {
+        (None, name)
+    };
+
+    let mut item = None;
+    let mut length = None;
+    for argument in arguments {
+        match argument {
+            Argument::Type(field_type) if base == "array" && item.is_none() => item = Some(field_type),
+            Argument::Named(key, ArgumentValue::Type(field_type)) if key == "of" && base == "array" && item.is_none() => {
+                item = Some(field_type);
+            }
+            Argument::Named(key, value) => {
+                length = Some(match (key.as_str(), value) {
+                    ("prefix", ArgumentValue::Type(prefix)) => Length::Prefix(Box::new(prefix)),
+                    ("count" | "len", ArgumentValue::Number(number)) => Length::Fixed(number),
+                    ("count" | "len", ArgumentValue::Name(field)) => Length::Field(field),
+                    (key, _) => return Err(SchemaError::new(format!("invalid argument {} for {}", key, name))),
+                });
+            }
+            Argument::Type(_) => return Err(SchemaError::new(format!("unexpected type argument for {}", name))),
+        }
+    }
+
+    let scalar = match base {
+        "u16" => Some(FieldType::U16(order)),
+        "u32" => Some(FieldType::U32(order)),
+        "u64" => Some(FieldType::U64(order)),
+        "i16" => Some(FieldType::I16(order)),
+        "i32" => Some(FieldType::I32(order)),
+        "i64" => Some(FieldType::I64(order)),
+        "f32" => Some(FieldType::F32(order)),
+        "f64" => Some(FieldType::F64(order)),
+        _ => None,
+    };
+    let field_type = match (scalar, name) {
+        (Some(field_type), _) => field_type,
+        (None, "bool") => FieldType::Bool,
+        (None, "u8") => FieldType::U8,
+        (None, "i8") => FieldType::I8,
+        (None, "varint") => FieldType::VarInt,
+        (None, "cstring") => FieldType::CString,
+        // Defaults to the same u32 prefix as `read_string_with_length`
+        (None, "string") => FieldType::String(length.take().unwrap_or(Length::Prefix(Box::new(FieldType::U32(None))))),
+        (None, "bytes") => FieldType::Bytes(length.take().ok_or_else(|| SchemaError::new("bytes needs a len or prefix"))?),
+        (None, "array") => FieldType::Array(
+            Box::new(item.take().ok_or_else(|| SchemaError::new("array needs an item type"))?),
+            length.take().ok_or_else(|| SchemaError::new("array needs a count or prefix"))?,
+        ),
+        (None, name) => return Err(SchemaError::new(format!("unknown type {}", name))),
+    };
+    if length.is_some() {
+        return Err(SchemaError::new(format!("{} does not take a length", name)));
+    }
+    Ok(field_type)
+}
+
+//#endregion Text description parser
+
+//#region JSON description parser
+
+fn schema_from_value(value: &Value) -> Result<Schema, SchemaError> {
+    let fields = match value {
+        Value::Array(fields) => fields,
+        Value::Struct(_) => match value.get("fields") {
+            Some(Value::Array(fields)) => fields,
+            _ => return Err(SchemaError::new("expected a fields array")),
+        },
+        _ => return Err(SchemaError::new("expected an array of fields")),
+    };
+    let mut schema = Schema::new();
+    for field in fields {
+        let name = field.get("name").and_then(Value::as_str).ok_or_else(|| SchemaError::new("field needs a name"))?;
+        let field_type = type_from_value(field).map_err(|e| e.within(name))?;
+        schema = schema.field(name, field_type);
+    }
+    Ok(schema)
+}
+
+// Builds a type from either a text type description or an object with a `type` key and arguments
+fn type_from_value(value: &Value) -> Result<FieldType, SchemaError> {
+    let fields = match value {
+        Value::String(text) => return FieldType::parse(text),
+        Value::Struct(fields) => fields,
+        _ => return Err(SchemaError::new("expected a type")),
+    };
+    let name = match value.get("type") {
+        Some(Value::String(name)) => name,
+        // An object with just a nested type, like `{ "type": { "type": "array", ... } }`
+        Some(nested @ Value::Struct(_)) => return type_from_value(nested),
+        _ => return Err(SchemaError::new("field needs a type")),
+    };
+    if name == "struct" {
+        return schema_from_value(value).map(FieldType::Struct);
+    }
+    let mut arguments = Vec::new();
+    for (key, value) in fields {
+        let value = match (key.as_str(), value) {
+            ("type" | "name", _) => continue,
+            ("count" | "len", Value::String(field)) => ArgumentValue::Name(field.clone()),
+            ("count" | "len", value) => {
+                let count = value.as_u64().and_then(|count| usize::try_from(count).ok());
+                ArgumentValue::Number(count.ok_or_else(|| SchemaError::new(format!("invalid {}", key)))?)
+            }
+            ("of" | "prefix", value) => ArgumentValue::Type(type_from_value(value)?),
+            (key, _) => return Err(SchemaError::new(format!("invalid argument {}", key))),
+        };
+        arguments.push(Argument::Named(key.clone(), value));
+    }
+    if arguments.is_empty() {
+        // A plain text type such as `u16le` or `string(prefix=u8)`
+        return type_from_value(&Value::String(name.clone()));
+    }
+    build_type(name, arguments)
+}
+
+//#endregion JSON description parser
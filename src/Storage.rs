@@ -0,0 +1,148 @@
+use alloc::vec::Vec;
+
+/// The bytes behind a buffer.
+///
+/// Every storage can be read. Storage that also implements `StorageMut` can be written to.
+pub trait Storage {
+    /// What the buffer keeps next to the storage to know how many of its bytes are written.
+    /// Storage that knows its own length keeps nothing, `()`, so there is no second count to drift.
+    /// Fixed-size storage is all capacity, so it keeps the number of bytes written as a `usize` cursor.
+    type Length: Copy;
+
+    /// Gets the length of storage whose bytes are all written.
+    fn full_length(&self) -> Self::Length;
+
+    /// Gets the written bytes of the storage.
+    fn written(&self, length: Self::Length) -> &[u8];
+
+    /// Drops every written byte past `to`, if the storage can shrink.
+    /// Storage that is never written has nothing to drop.
+    fn truncate(&mut self, _length: &mut Self::Length, _to: usize) {}
+}
+
+/// Storage that can be written to.
+pub trait StorageMut: Storage {
+    /// Gets the largest number of bytes the storage can hold.
+    fn max_len(&self) -> usize;
+
+    /// Drops every written byte, and gets the length of the now empty storage.
+    fn clear(&mut self) -> Self::Length;
+
+    /// Writes bytes after the written ones.
+    /// The buffer makes sure the bytes fit within `max_len`.
+    fn append(&mut self, length: &mut Self::Length, bytes: &[u8]);
+}
+
+//#region Vec storage
+
+// The vector only ever holds the written bytes, so it is its own length
+
+impl Storage for Vec<u8> {
+    type Length = ();
+
+    fn full_length(&self) {}
+
+    fn written(&self, _length: ()) -> &[u8] {
+        self
+    }
+
+    fn truncate(&mut self, _length: &mut (), to: usize) {
+        Vec::truncate(self, to);
+    }
+}
+
+impl StorageMut for Vec<u8> {
+    fn max_len(&self) -> usize {
+        isize::MAX as usize
+    }
+
+    fn clear(&mut self) {
+        Vec::clear(self);
+    }
+
+    fn append(&mut self, _length: &mut (), bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+}
+
+//#endregion Vec storage
+
+//#region Borrowed storage
+
+impl Storage for &[u8] {
+    type Length = ();
+
+    fn full_length(&self) {}
+
+    fn written(&self, _length: ()) -> &[u8] {
+        self
+    }
+}
+
+impl Storage for &mut [u8] {
+    type Length = usize;
+
+    fn full_length(&self) -> usize {
+        self.len()
+    }
+
+    fn written(&self, length: usize) -> &[u8] {
+        &self[..length]
+    }
+
+    fn truncate(&mut self, length: &mut usize, to: usize) {
+        *length = to.min(*length);
+    }
+}
+
+impl StorageMut for &mut [u8] {
+    fn max_len(&self) -> usize {
+        self.len()
+    }
+
+    fn clear(&mut self) -> usize {
+        0
+    }
+
+    fn append(&mut self, length: &mut usize, bytes: &[u8]) {
+        self[*length..*length + bytes.len()].copy_from_slice(bytes);
+        *length += bytes.len();
+    }
+}
+
+//#endregion Borrowed storage
+
+//#region Array storage
+
+impl<const N: usize> Storage for [u8; N] {
+    type Length = usize;
+
+    fn full_length(&self) -> usize {
+        N
+    }
+
+    fn written(&self, length: usize) -> &[u8] {
+        &self[..length]
+    }
+
+    fn truncate(&mut self, length: &mut usize, to: usize) {
+        *length = to.min(*length);
+    }
+}
+
+impl<const N: usize> StorageMut for [u8; N] {
+    fn max_len(&self) -> usize {
+        N
+    }
+
+    fn clear(&mut self) -> usize {
+        0
+    }
+
+    fn append(&mut self, length: &mut usize, bytes: &[u8]) {
+        self[*length..*length + bytes.len()].copy_from_slice(bytes);
+        *length += bytes.len();
+    }
+}
+
+//#endregion Array storage
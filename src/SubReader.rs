@@ -0,0 +1,96 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::Buffer::Buffer;
+use crate::Storage::Storage;
+
+/// A read-only view of the next bytes of a buffer, created by `Buffer::sub_reader`.
+///
+/// Reads can't go past the end of the region, and positions are relative to its start.
+/// When the view is dropped, the parent's position moves past the whole region, however much of it was read.
+///
+/// ```rust
+/// use muscleman::Buffer::Buffer;
+///
+/// let mut buffer = Buffer::wrap(vec![0x00, 0x02, 0xAA, 0xBB, 0xCC]);
+/// let length = buffer.read_u16().unwrap() as usize;
+/// {
+///     let mut body = buffer.sub_reader(length).unwrap();
+///     assert_eq!(body.read_u8(), Some(0xAA));
+///     assert_eq!(body.read_u16(), None);
+/// }
+/// assert_eq!(buffer.read_u8(), Some(0xCC));
+/// ```
+pub struct SubReader<'a> {
+    // A buffer over the region's bytes
+    reader: Buffer<&'a [u8]>,
+
+    // The parent's position, moved to the end of the region on drop
+    parent_position: &'a mut usize,
+
+    // The parent's allocation counter, which reads in the region count against
+    parent_allocated: &'a mut usize,
+
+    // The offset of the end of the region in the parent
+    end: usize,
+}
+
+impl SubReader<'_> {
+    /// Finishes reading the region, returning the number of bytes left unread.
+    pub fn finish(self) -> usize {
+        self.reader.remaining()
+    }
+
+    /// Finishes reading the region, failing if any of its bytes were left unread.
+    pub fn expect_end(self) -> Option<()> {
+        if self.reader.remaining() != 0 {
+            return None;
+        }
+        Some(())
+    }
+}
+
+impl<'a> Deref for SubReader<'a> {
+    type Target = Buffer<&'a [u8]>;
+
+    fn deref(&self) -> &Buffer<&'a [u8]> {
+        &self.reader
+    }
+}
+
+impl<'a> DerefMut for SubReader<'a> {
+    fn deref_mut(&mut self) -> &mut Buffer<&'a [u8]> {
+        &mut self.reader
+    }
+}
+
+impl Drop for SubReader<'_> {
+    fn drop(&mut self) {
+        *self.parent_position = self.end;
+        *self.parent_allocated = self.reader.allocated;
+    }
+}
+
+impl<S: Storage> Buffer<S> {
+
+    //#region Sub-reader methods
+
+    /// Creates a view that can only read the next `len` bytes, such as the body of a length-prefixed record.
+    /// The view inherits the byte order, string settings and decode limits, and aligns from the start of the region.
+    /// Fails if `len` is past the end of the buffer.
+    pub fn sub_reader(&mut self, len: usize) -> Option<SubReader<'_>> {
+        if len > self.remaining() {
+            return None;
+        }
+        let end = self.position + len;
+        let mut reader = Buffer::wrap(&self.data.written(self.length)[self.position..end]);
+        reader.byte_order = self.byte_order;
+        reader.string_encoding = self.string_encoding;
+        reader.string_terminator = self.string_terminator;
+        reader.decode_limits = self.decode_limits;
+        reader.allocated = self.allocated;
+        reader.depth = self.depth;
+        Some(SubReader { reader, parent_position: &mut self.position, parent_allocated: &mut self.allocated, end })
+    }
+
+    //#endregion Sub-reader methods
+}
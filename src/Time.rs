@@ -0,0 +1,185 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::Buffer::Buffer;
+use crate::Storage::{Storage, StorageMut};
+
+/// How a timestamp is encoded. Every field is in the buffer's byte order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TimeFormat {
+    /// Seconds since the Unix epoch as a u32, from 1970 to 2106
+    UnixSeconds32,
+
+    /// Seconds since the Unix epoch as an i64
+    UnixSeconds64,
+
+    /// Milliseconds since the Unix epoch as an i64
+    UnixMillis,
+
+    /// Nanoseconds since the Unix epoch as an i64, from 1677 to 2262
+    UnixNanos,
+
+    /// NTP timestamp: a u32 of seconds since 1900 followed by a u32 fraction of a second, from 1900 to 2036
+    Ntp64,
+
+    /// Windows FILETIME: a u64 of 100-nanosecond intervals since 1601
+    FileTime,
+
+    /// MS-DOS date and time: a u16 time followed by a u16 date, from 1980 to 2107, to the even second.
+    /// DOS stores local time, which is read and written as UTC.
+    DosDateTime,
+}
+
+// The number of nanoseconds in a second
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+// The seconds from the NTP epoch, 1900-01-01, to the Unix epoch
+const NTP_EPOCH_OFFSET: i128 = 2_208_988_800;
+
+// The 100-nanosecond intervals from the FILETIME epoch, 1601-01-01, to the Unix epoch
+const FILETIME_EPOCH_OFFSET: i128 = 116_444_736_000_000_000;
+
+// The number of seconds in a day
+const SECONDS_PER_DAY: i128 = 86_400;
+
+// Converts nanoseconds since the Unix epoch to a time, failing if the platform can't represent it
+fn system_time_from_nanos(nanos: i128) -> Option<SystemTime> {
+    let duration = |nanos: u128| {
+        let seconds = u64::try_from(nanos / NANOS_PER_SECOND as u128).ok()?;
+        Some(Duration::new(seconds, (nanos % NANOS_PER_SECOND as u128) as u32))
+    };
+    if nanos >= 0 {
+        UNIX_EPOCH.checked_add(duration(nanos.unsigned_abs())?)
+    } else {
+        UNIX_EPOCH.checked_sub(duration(nanos.unsigned_abs())?)
+    }
+}
+
+// Converts a time to nanoseconds since the Unix epoch, negative before it
+fn nanos_from_system_time(time: SystemTime) -> i128 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => after.as_nanos() as i128,
+        Err(before) => -(before.duration().as_nanos() as i128),
+    }
+}
+
+// Gets the days since 1970-01-01 of a date in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = month as i64;
+    let day_of_year = (153 * if month > 2 { month - 3 } else { month + 9 } + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+// Gets the year, month and day of a number of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+    (year_of_era + era * 400 + (month <= 2) as i64, month, day)
+}
+
+// Unpacks a DOS date and time into seconds since the Unix epoch, failing on an impossible date or time
+fn dos_to_unix_seconds(time: u16, date: u16) -> Option<i128> {
+    let (year, month, day) = (1980 + (date >> 9) as i64, ((date >> 5) & 0x0F) as u32, (date & 0x1F) as u32);
+    let (hour, minute, second) = ((time >> 11) as i128, ((time >> 5) & 0x3F) as i128, ((time & 0x1F) * 2) as i128);
+    let days = days_from_civil(year, month, day);
+    if !(1..=12).contains(&month) || civil_from_days(days) != (year, month, day) {
+        return None;
+    }
+    if hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    Some(days as i128 * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second)
+}
+
+// Packs seconds since the Unix epoch into a DOS time and date, rounding down to the even second
+fn unix_seconds_to_dos(seconds: i128) -> Option<(u16, u16)> {
+    let days = i64::try_from(seconds.div_euclid(SECONDS_PER_DAY)).ok()?;
+    let second_of_day = seconds.rem_euclid(SECONDS_PER_DAY) as u32;
+    let (year, month, day) = civil_from_days(days);
+    if !(1980..=2107).contains(&year) {
+        return None;
+    }
+    let time = ((second_of_day / 3600) << 11) | ((second_of_day / 60 % 60) << 5) | (second_of_day % 60 / 2);
+    let date = (((year - 1980) as u32) << 9) | (month << 5) | day;
+    Some((time as u16, date as u16))
+}
+
+impl<S: Storage> Buffer<S> {
+
+    //#region Timestamp reading methods
+
+    /// Reads a timestamp in the given format.
+    /// Fails if the timestamp is an impossible DOS date or time, or outside what `SystemTime` can hold.
+    pub fn read_timestamp(&mut self, format: TimeFormat) -> Option<SystemTime> {
+        self.atomic(|buffer| {
+            let nanos = match format {
+                TimeFormat::UnixSeconds32 => buffer.read_u32()? as i128 * NANOS_PER_SECOND,
+                TimeFormat::UnixSeconds64 => buffer.read_i64()? as i128 * NANOS_PER_SECOND,
+                TimeFormat::UnixMillis => buffer.read_i64()? as i128 * 1_000_000,
+                TimeFormat::UnixNanos => buffer.read_i64()? as i128,
+                TimeFormat::Ntp64 => {
+                    let seconds = buffer.read_u32()? as i128 - NTP_EPOCH_OFFSET;
+                    let fraction = buffer.read_u32()? as i128;
+                    seconds * NANOS_PER_SECOND + ((fraction * NANOS_PER_SECOND) >> 32)
+                }
+                TimeFormat::FileTime => (buffer.read_u64()? as i128 - FILETIME_EPOCH_OFFSET) * 100,
+                TimeFormat::DosDateTime => {
+                    let time = buffer.read_u16()?;
+                    let date = buffer.read_u16()?;
+                    dos_to_unix_seconds(time, date)? * NANOS_PER_SECOND
+                }
+            };
+            system_time_from_nanos(nanos)
+        })
+    }
+
+    //#endregion Timestamp reading methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region Timestamp writing methods
+
+    /// Writes a timestamp in the given format, rounding down to the format's precision.
+    /// Fails if the time is outside the format's range, and then writes nothing.
+    pub fn write_timestamp(&mut self, time: SystemTime, format: TimeFormat) -> Option<()> {
+        let nanos = nanos_from_system_time(time);
+        let seconds = nanos.div_euclid(NANOS_PER_SECOND);
+        match format {
+            TimeFormat::UnixSeconds32 => self.write_u32(u32::try_from(seconds).ok()?),
+            TimeFormat::UnixSeconds64 => self.write_i64(i64::try_from(seconds).ok()?),
+            TimeFormat::UnixMillis => self.write_i64(i64::try_from(nanos.div_euclid(1_000_000)).ok()?),
+            TimeFormat::UnixNanos => self.write_i64(i64::try_from(nanos).ok()?),
+            TimeFormat::Ntp64 => {
+                let ntp_seconds = u32::try_from(seconds + NTP_EPOCH_OFFSET).ok()?;
+                // Rounding the fraction up makes reading it back give the same nanosecond
+                let fraction = ((nanos.rem_euclid(NANOS_PER_SECOND) << 32) + NANOS_PER_SECOND - 1) / NANOS_PER_SECOND;
+                if !self.has_space(8) {
+                    return None;
+                }
+                self.write_u32(ntp_seconds)?;
+                self.write_u32(fraction as u32)
+            }
+            TimeFormat::FileTime => self.write_u64(u64::try_from(nanos.div_euclid(100) + FILETIME_EPOCH_OFFSET).ok()?),
+            TimeFormat::DosDateTime => {
+                let (time, date) = unix_seconds_to_dos(seconds)?;
+                if !self.has_space(4) {
+                    return None;
+                }
+                self.write_u16(time)?;
+                self.write_u16(date)
+            }
+        }
+    }
+
+    //#endregion Timestamp writing methods
+}
@@ -0,0 +1,72 @@
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+
+use crate::Buffer::Buffer;
+use crate::Storage::{Storage, StorageMut};
+
+// Swaps between the RFC 4122 byte order and Microsoft's GUID layout,
+// which stores the first three fields little-endian and the rest as they are
+fn swap_guid_fields(mut bytes: [u8; 16]) -> [u8; 16] {
+    bytes[0..4].reverse();
+    bytes[4..6].reverse();
+    bytes[6..8].reverse();
+    bytes
+}
+
+impl<S: Storage> Buffer<S> {
+
+    //#region UUID reading methods
+
+    /// Reads the 16 bytes of a UUID in RFC 4122 byte order, whatever the buffer's byte order.
+    pub fn read_uuid_bytes(&mut self) -> Option<[u8; 16]> {
+        self.take()
+    }
+
+    /// Reads a Microsoft GUID, whose first three fields are little-endian, returning its bytes in RFC 4122 order.
+    pub fn read_guid_bytes(&mut self) -> Option<[u8; 16]> {
+        self.take().map(swap_guid_fields)
+    }
+
+    /// Reads a UUID in RFC 4122 byte order.
+    #[cfg(feature = "uuid")]
+    pub fn read_uuid(&mut self) -> Option<Uuid> {
+        self.read_uuid_bytes().map(Uuid::from_bytes)
+    }
+
+    /// Reads a UUID stored as a Microsoft GUID, whose first three fields are little-endian.
+    #[cfg(feature = "uuid")]
+    pub fn read_guid(&mut self) -> Option<Uuid> {
+        self.read_guid_bytes().map(Uuid::from_bytes)
+    }
+
+    //#endregion UUID reading methods
+}
+
+impl<S: StorageMut> Buffer<S> {
+
+    //#region UUID writing methods
+
+    /// Writes the 16 bytes of a UUID in RFC 4122 byte order, whatever the buffer's byte order.
+    pub fn write_uuid_bytes(&mut self, bytes: &[u8; 16]) -> Option<()> {
+        self.write_bytes(bytes)
+    }
+
+    /// Writes the bytes of a UUID, given in RFC 4122 order, as a Microsoft GUID whose first three fields are little-endian.
+    pub fn write_guid_bytes(&mut self, bytes: &[u8; 16]) -> Option<()> {
+        self.write_bytes(&swap_guid_fields(*bytes))
+    }
+
+    /// Writes a UUID in RFC 4122 byte order.
+    #[cfg(feature = "uuid")]
+    pub fn write_uuid(&mut self, uuid: &Uuid) -> Option<()> {
+        self.write_uuid_bytes(uuid.as_bytes())
+    }
+
+    /// Writes a UUID as a Microsoft GUID, whose first three fields are little-endian.
+    #[cfg(feature = "uuid")]
+    pub fn write_guid(&mut self, uuid: &Uuid) -> Option<()> {
+        self.write_guid_bytes(uuid.as_bytes())
+    }
+
+    //#endregion UUID writing methods
+}
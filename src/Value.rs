@@ -0,0 +1,426 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::DecodeLimits::DecodeLimits;
+use crate::Schema::SchemaError;
+
+/// A dynamically typed value decoded from a buffer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Array(Vec<Value>),
+
+    /// Named fields, in the order they appear on the wire
+    Struct(Vec<(String, Value)>),
+
+    /// Key-value pairs whose keys aren't all strings, in the order they appear on the wire
+    Map(Vec<(Value, Value)>),
+
+    /// Application-specific data tagged with a type, like MessagePack extension types
+    Ext(i8, Vec<u8>),
+
+    /// A value with a semantic tag, like CBOR tags
+    Tag(u64, Box<Value>),
+
+    /// A simple value without a meaning of its own, like CBOR simple values
+    Simple(u8),
+}
+
+impl Value {
+
+    //#region Accessors
+
+    /// Gets a field of a struct by name.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Struct(fields) => fields.iter().find(|(field, _)| field == name).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a signed integer, if it fits.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Int(value) => Some(*value),
+            Value::UInt(value) => i64::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an unsigned integer, if it fits.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Int(value) => u64::try_from(*value).ok(),
+            Value::UInt(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a floating-point number.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(value) => Some(*value as f64),
+            Value::UInt(value) => Some(*value as f64),
+            Value::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a string slice.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as raw bytes.
+    /// Arrays of integers between 0 and 255 are accepted, since that is how bytes are written to JSON.
+    pub fn as_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            Value::Bytes(bytes) => Some(bytes.clone()),
+            Value::Array(items) => items.iter().map(|item| item.as_u64().and_then(|byte| u8::try_from(byte).ok())).collect(),
+            _ => None,
+        }
+    }
+
+    //#endregion Accessors
+
+    // Turns the alternating keys and values of a decoded map into a struct, or a map if any key isn't a string
+    pub(crate) fn from_alternating(items: Vec<Value>) -> Value {
+        let mut entries = Vec::with_capacity(items.len() / 2);
+        let mut items = items.into_iter();
+        while let (Some(key), Some(value)) = (items.next(), items.next()) {
+            entries.push((key, value));
+        }
+        if entries.iter().all(|(key, _)| matches!(key, Value::String(_))) {
+            let fields = entries.into_iter().map(|(key, value)| match key {
+                Value::String(name) => (name, value),
+                _ => unreachable!(),
+            });
+            Value::Struct(fields.collect())
+        } else {
+            Value::Map(entries)
+        }
+    }
+
+    //#region JSON methods
+
+    /// Parses a JSON document into a value.
+    /// Objects become structs, keeping the order of their keys.
+    pub fn from_json(text: &str) -> Result<Value, SchemaError> {
+        let mut parser = JsonParser { text: text.as_bytes(), position: 0, depth: 0 };
+        let value = parser.parse_value()?;
+        parser.skip_whitespace();
+        if parser.position != parser.text.len() {
+            return Err(parser.error("trailing characters"));
+        }
+        Ok(value)
+    }
+
+    /// Renders the value as compact JSON.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out, None, 0);
+        out
+    }
+
+    /// Renders the value as indented JSON.
+    pub fn to_json_pretty(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out, Some(2), 0);
+        out
+    }
+
+    fn write_json(&self, out: &mut String, indent: Option<usize>, depth: usize) {
+        match self {
+            Value::Null => out.push_str("null"),
+            Value::Bool(value) => write!(out, "{}", value).unwrap(),
+            Value::Int(value) => write!(out, "{}", value).unwrap(),
+            Value::UInt(value) => write!(out, "{}", value).unwrap(),
+            Value::Float(value) if value.is_finite() => write!(out, "{:?}", value).unwrap(),
+            Value::Float(_) => out.push_str("null"),
+            Value::String(value) => write_json_string(out, value),
+            Value::Bytes(bytes) => {
+                let items: Vec<Value> = bytes.iter().map(|byte| Value::UInt(*byte as u64)).collect();
+                Value::Array(items).write_json(out, None, depth);
+            }
+            Value::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_json_newline(out, indent, depth + 1);
+                    item.write_json(out, indent, depth + 1);
+                }
+                if !items.is_empty() {
+                    write_json_newline(out, indent, depth);
+                }
+                out.push(']');
+            }
+            Value::Map(entries) => {
+                // JSON keys must be strings, so entries are written as [key, value] pairs
+                let pairs = entries.iter().map(|(key, value)| Value::Array(vec![key.clone(), value.clone()])).collect();
+                Value::Array(pairs).write_json(out, indent, depth);
+            }
+            Value::Tag(tag, value) => {
+                let fields = vec![(String::from("tag"), Value::UInt(*tag)), (String::from("value"), (**value).clone())];
+                Value::Struct(fields).write_json(out, indent, depth);
+            }
+            Value::Simple(value) => {
+                let fields = vec![(String::from("simple"), Value::UInt(*value as u64))];
+                Value::Struct(fields).write_json(out, indent, depth);
+            }
+            Value::Ext(ext_type, data) => {
+                let fields = vec![(String::from("type"), Value::Int(*ext_type as i64)), (String::from("data"), Value::Bytes(data.clone()))];
+                Value::Struct(fields).write_json(out, indent, depth);
+            }
+            Value::Struct(fields) => {
+                out.push('{');
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_json_newline(out, indent, depth + 1);
+                    write_json_string(out, name);
+                    out.push(':');
+                    if indent.is_some() {
+                        out.push(' ');
+                    }
+                    value.write_json(out, indent, depth + 1);
+                }
+                if !fields.is_empty() {
+                    write_json_newline(out, indent, depth);
+                }
+                out.push('}');
+            }
+        }
+    }
+
+    //#endregion JSON methods
+}
+
+fn write_json_newline(out: &mut String, indent: Option<usize>, depth: usize) {
+    if let Some(indent) = indent {
+        out.push('\n');
+        out.push_str(&" ".repeat(indent * depth));
+    }
+}
+
+fn write_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// A small recursive descent JSON parser, so the crate doesn't need a dependency for it
+struct JsonParser<'a> {
+    text: &'a [u8],
+    position: usize,
+    depth: usize,
+}
+
+impl JsonParser<'_> {
+    fn error(&self, message: &str) -> SchemaError {
+        SchemaError::new(format!("invalid JSON at byte {}: {}", self.position, message))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.position < self.text.len() && self.text[self.position].is_ascii_whitespace() {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.position).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), SchemaError> {
+        self.skip_whitespace();
+        if self.peek() == Some(byte) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", byte as char)))
+        }
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: Value) -> Result<Value, SchemaError> {
+        if self.text[self.position..].starts_with(literal.as_bytes()) {
+            self.position += literal.len();
+            Ok(value)
+        } else {
+            Err(self.error("unknown literal"))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, SchemaError> {
+        if self.depth >= DecodeLimits::DEFAULT_MAX_NESTING_DEPTH {
+            return Err(self.error("nesting depth exceeds the limit"));
+        }
+        self.depth += 1;
+        let value = self.parse_nested_value();
+        self.depth -= 1;
+        value
+    }
+
+    fn parse_nested_value(&mut self) -> Result<Value, SchemaError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'n') => self.parse_literal("null", Value::Null),
+            Some(b't') => self.parse_literal("true", Value::Bool(true)),
+            Some(b'f') => self.parse_literal("false", Value::Bool(false)),
+            Some(b'"') => self.parse_string().map(Value::String),
+            Some(b'[') => {
+                self.position += 1;
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b']') {
+                    self.position += 1;
+                    return Ok(Value::Array(items));
+                }
+                loop {
+                    items.push(self.parse_value()?);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.position += 1,
+                        Some(b']') => {
+                            self.position += 1;
+                            return Ok(Value::Array(items));
+                        }
+                        _ => return Err(self.error("expected ',' or ']'")),
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.position += 1;
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b'}') {
+                    self.position += 1;
+                    return Ok(Value::Struct(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let name = self.parse_string()?;
+                    self.expect(b':')?;
+                    fields.push((name, self.parse_value()?));
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.position += 1,
+                        Some(b'}') => {
+                            self.position += 1;
+                            return Ok(Value::Struct(fields));
+                        }
+                        _ => return Err(self.error("expected ',' or '}'")),
+                    }
+                }
+            }
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value, SchemaError> {
+        let start = self.position;
+        let mut is_float = false;
+        while let Some(byte) = self.peek() {
+            match byte {
+                b'0'..=b'9' | b'-' | b'+' => {}
+                b'.' | b'e' | b'E' => is_float = true,
+                _ => break,
+            }
+            self.position += 1;
+        }
+        let text = core::str::from_utf8(&self.text[start..self.position]).unwrap();
+        let value = if is_float {
+            text.parse().ok().map(Value::Float)
+        } else if text.starts_with('-') {
+            text.parse().ok().map(Value::Int)
+        } else {
+            text.parse().ok().map(Value::UInt)
+        };
+        value.ok_or_else(|| self.error("invalid number"))
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, SchemaError> {
+        let digits = self.text.get(self.position..self.position + 4).ok_or_else(|| self.error("truncated escape"))?;
+        let digits = core::str::from_utf8(digits).map_err(|_| self.error("invalid escape"))?;
+        let value = u32::from_str_radix(digits, 16).map_err(|_| self.error("invalid escape"))?;
+        self.position += 4;
+        Ok(value)
+    }
+
+    fn parse_string(&mut self) -> Result<String, SchemaError> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            let byte = self.peek().ok_or_else(|| self.error("unterminated string"))?;
+            self.position += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let escape = self.peek().ok_or_else(|| self.error("unterminated string"))?;
+                    self.position += 1;
+                    let c = match escape {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let mut code = self.parse_hex4()?;
+                            if (0xD800..0xDC00).contains(&code) && self.text[self.position..].starts_with(b"\\u") {
+                                self.position += 2;
+                                let low = self.parse_hex4()?;
+                                // A high surrogate can only be followed by a low one
+                                if !(0xDC00..0xE000).contains(&low) {
+                                    return Err(self.error("invalid surrogate pair"));
+                                }
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            }
+                            char::from_u32(code).ok_or_else(|| self.error("invalid escape"))?
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    let mut encoded = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut encoded).as_bytes());
+                }
+                byte => bytes.push(byte),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("invalid UTF-8"))
+    }
+}
@@ -25,7 +25,7 @@
 //! ### Creating a buffer
 //!
 //! ```rust
-//! use muscleman::Buffer;
+//! use muscleman::Buffer::Buffer;
 //!
 //! let mut buffer = Buffer::new();
 //!
@@ -38,7 +38,7 @@
 //! ### Writing to a buffer
 //!
 //! ```rust
-//! use muscleman::Buffer;
+//! use muscleman::Buffer::Buffer;
 //!
 //! let mut buffer = Buffer::new();
 //!
@@ -67,31 +67,71 @@
 //! ### Reading from a buffer
 //!
 //! ```rust
-//! use muscleman::Buffer;
+//! use muscleman::Buffer::Buffer;
 //!
 //! let mut buffer = Buffer::new();
+//! # buffer.write_i8(1);
+//! # buffer.write_i16(2);
+//! # buffer.write_i32(3);
+//! # buffer.write_i64(4);
+//! # buffer.write_u8(5);
+//! # buffer.write_u16(6);
+//! # buffer.write_u32(7);
+//! # buffer.write_u64(8);
+//! # buffer.write_f32(9.0_f32);
+//! # buffer.write_f64(10.0_f64);
+//! # buffer.write_string("Hello, world!");
+//! # buffer.write_string_with_length("Hello, world!");
 //!
 //! // Assume the data in the "Writing to a buffer" is currently in the buffer
 //!
 //! // 8 to 64 bit signed integers
-//! assert_eq!(buffer.read_i8(), 1);
-//! assert_eq!(buffer.read_i16(), 2);
-//! assert_eq!(buffer.read_i32(), 3);
-//! assert_eq!(buffer.read_i64(), 4);
+//! assert_eq!(buffer.read_i8(), Some(1));
+//! assert_eq!(buffer.read_i16(), Some(2));
+//! assert_eq!(buffer.read_i32(), Some(3));
+//! assert_eq!(buffer.read_i64(), Some(4));
 //!
 //! // 8 to 64 bit unsigned integers
-//! assert_eq!(buffer.read_u8(), 5);
-//! assert_eq!(buffer.read_u16(), 6);
-//! assert_eq!(buffer.read_u32(), 7);
-//! assert_eq!(buffer.read_u64(), 8);
+//! assert_eq!(buffer.read_u8(), Some(5));
+//! assert_eq!(buffer.read_u16(), Some(6));
+//! assert_eq!(buffer.read_u32(), Some(7));
+//! assert_eq!(buffer.read_u64(), Some(8));
 //!
 //! // 32 and 64 bit floating point numbers
-//! assert_eq!(buffer.read_f32(), 9.0_f32);
-//! assert_eq!(buffer.read_f64(), 10.0_f64);
+//! assert_eq!(buffer.read_f32(), Some(9.0_f32));
+//! assert_eq!(buffer.read_f64(), Some(10.0_f64));
 //!
 //! // Null terminated strings and length prefixed strings
-//! assert_eq!(buffer.read_string(), "Hello, world!");
-//! assert_eq!(buffer.read_string_with_length(), "Hello, world!");
+//! assert_eq!(buffer.read_string(), Some("Hello, world!".to_string()));
+//! assert_eq!(buffer.read_string_with_length(), Some("Hello, world!".to_string()));
+//! ```
+//!
+//! ### Borrowed and fixed-size storage
+//!
+//! A buffer can also read a borrowed `&[u8]` without copying it, or write into a fixed-size
+//! `&mut [u8]` or `[u8; N]`. Writes that don't fit return `None`.
+//!
+//! ```rust
+//! use muscleman::Buffer::Buffer;
+//!
+//! let packet = [0x00, 0x2A, 0x01];
+//! let mut reader = Buffer::wrap(&packet[..]);
+//! assert_eq!(reader.read_u16(), Some(42));
+//!
+//! let mut out = [0; 2];
+//! let mut writer = Buffer::wrap_empty(&mut out[..]);
+//! assert_eq!(writer.write_u16(42), Some(()));
+//! assert_eq!(writer.write_u8(1), None);
+//! ```
+//!
+//! ## `no_std`
+//!
+//! The `std` feature is enabled by default. Without it the crate is `#![no_std]` and only needs `alloc`,
+//! and everything except the `std::io` integrations and the deflate codecs keeps working.
+//!
+//! ```toml
+//! [dependencies]
+//! muscleman = { version = "0.3.1", default-features = false }
 //! ```
 //!
 //! ## Why?
@@ -105,9 +145,39 @@
 //!
 //! `muscleman` is licensed under the MIT license. See the `LICENSE` file for more information.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![allow(non_snake_case)]
+
+extern crate alloc;
+
+pub mod Address;
+pub mod Base64;
+pub mod Bson;
 pub mod Buffer;
+pub mod Bulk;
 pub mod ByteOrder;
+pub mod Cbor;
+#[cfg(any(feature = "deflate", feature = "lz4"))]
+pub mod Compression;
+pub mod DecodeLimits;
+#[cfg(feature = "std")]
+pub mod File;
+pub mod Hex;
+pub mod HexDump;
+pub mod MessagePack;
+pub mod Minecraft;
+pub mod Nbt;
+pub mod Numeric;
+pub mod Padding;
+pub mod Pod;
+pub mod Protobuf;
+pub mod Schema;
+pub mod Storage;
+pub mod SubReader;
+#[cfg(feature = "std")]
+pub mod Time;
+pub mod Uuid;
+pub mod Value;
 
 #[cfg(test)]
 mod tests {
@@ -280,7 +350,7 @@ mod tests {
     fn read_string_with_length() {
         let mut buffer = Buffer::new();
         let value = "Hello, world!";
-        buffer.write_string(value);
+        buffer.write_string_with_length(value);
         let res = buffer.read_string_with_length();
         assert_eq!(res, Some(value.to_string()));
     }
@@ -366,4 +436,1769 @@ mod tests {
 		assert_eq!(r_string, Some(v_string.to_string()));
 		assert_eq!(r_string_with_length, Some(v_string_with_length.to_string()));
 	}
-}
\ No newline at end of file
+
+    //#region Compression tests
+
+    #[cfg(feature = "deflate")]
+    #[test]
+    fn compress_zlib_region() {
+        use super::Compression::Codec;
+
+        let mut body = Buffer::new();
+        body.write_u32(0x01020304);
+        body.write_string_with_length("Hello, world!");
+
+        let mut packet = Buffer::new();
+        packet.write_varint(body.len() as i64);
+        let written = body.compress_into(&mut packet, Codec::Zlib).unwrap();
+        packet.write_u8(0xFF);
+
+        assert_eq!(packet.read_varint(), Some(21));
+        let mut decoded = packet.read_compressed(written, Codec::Zlib).unwrap();
+        assert_eq!(decoded.read_u32(), Some(0x01020304));
+        assert_eq!(decoded.read_string_with_length(), Some("Hello, world!".to_string()));
+        assert_eq!(packet.read_u8(), Some(0xFF));
+    }
+
+    #[cfg(feature = "deflate")]
+    #[test]
+    fn read_compressed_invalid_keeps_position() {
+        use super::Compression::Codec;
+
+        let mut buffer = Buffer::new();
+        buffer.write_bytes(&[0x01, 0x02, 0x03, 0x04]);
+        assert!(buffer.read_compressed(4, Codec::Gzip).is_none());
+        assert!(buffer.read_compressed(5, Codec::Deflate).is_none());
+        assert_eq!(buffer.read_u32(), Some(0x01020304));
+    }
+
+    #[cfg(feature = "deflate")]
+    #[test]
+    fn read_compressed_inherits_settings() {
+        use super::ByteOrder::ByteOrder;
+        use super::Compression::Codec;
+        use super::DecodeLimits::DecodeLimits;
+
+        let mut body = Buffer::new();
+        body.write_bytes(&[0x01, 0x00, 0x00, 0x00]);
+        body.write_bytes(&[0xAA; 8]);
+        let mut packet = Buffer::new();
+        let written = body.compress_into(&mut packet, Codec::Zlib).unwrap();
+
+        // The 12 decompressed bytes are charged first, so reads from the region only have 7 left
+        packet.set_byte_order(ByteOrder::LittleEndian);
+        packet.set_decode_limits(DecodeLimits { max_total_allocation: 19, ..DecodeLimits::new() });
+        let mut decompressed = packet.read_compressed(written, Codec::Zlib).unwrap();
+        assert_eq!(decompressed.get_byte_order(), ByteOrder::LittleEndian);
+        assert_eq!(decompressed.read_u32(), Some(1));
+        assert_eq!(decompressed.read_bytes(8), None);
+        assert_eq!(decompressed.read_bytes(7), Some(vec![0xAA; 7]));
+    }
+
+    #[cfg(feature = "lz4")]
+    #[test]
+    fn compress_lz4_region() {
+        use super::Compression::Codec;
+
+        let mut body = Buffer::new();
+        body.write_bytes(&[0xAB; 256]);
+
+        let mut packet = Buffer::new();
+        let written = body.compress_into(&mut packet, Codec::Lz4).unwrap();
+        assert!(written < 256);
+
+        let mut decoded = packet.read_compressed(written, Codec::Lz4).unwrap();
+        assert_eq!(decoded.read_bytes(256), Some(vec![0xAB; 256]));
+        assert_eq!(decoded.read_byte(), None);
+    }
+
+    //#endregion Compression tests
+
+    //#region Hex dump tests
+
+    #[test]
+    fn debug_buffer() {
+        let mut buffer = Buffer::new();
+        buffer.write_u32(0x01020304);
+        buffer.read_u8();
+        assert_eq!(
+            format!("{:?}", buffer),
+            "Buffer { length: 4, position: 1, mark: None, byte_order: BigEndian }"
+        );
+    }
+
+    #[test]
+    fn hexdump_buffer() {
+        let mut buffer = Buffer::new();
+        buffer.write_string("Hello, world!");
+        buffer.write_u32(0xDEADBEEF);
+        buffer.read_bytes(3);
+        let expected = concat!(
+            "00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 00 de ad  |Hello, world!...|\n",
+            "                   ^^ position (0x3)\n",
+            "00000010  be ef                                             |..|\n",
+        );
+        assert_eq!(buffer.hexdump(), expected);
+    }
+
+    #[test]
+    fn hexdump_with_options() {
+        use super::HexDump::HexDumpOptions;
+
+        let mut buffer = Buffer::new();
+        buffer.write_bytes(&[0x00, 0x01, 0x41, 0x42, 0x43, 0x7F]);
+        buffer.read_bytes(6);
+        let options = HexDumpOptions { range: Some(2..6), width: 4, group: 2, ..HexDumpOptions::new() };
+        assert_eq!(
+            buffer.hexdump_with_options(&options),
+            concat!(
+                "00000002  41 42  43 7f  |ABC.|\n",
+                "                        ^^ position (0x6)\n",
+            )
+        );
+    }
+
+    #[test]
+    fn hexdump_huge_width() {
+        use super::HexDump::HexDumpOptions;
+
+        let buffer = Buffer::wrap(vec![0x41; 300]);
+        let options = HexDumpOptions { range: Some(1..3), width: usize::MAX, annotate: false, ..HexDumpOptions::new() };
+        assert!(buffer.hexdump_with_options(&options).starts_with("00000001  41 41    "));
+
+        // Widths past the maximum are clamped, so the buffer takes two lines
+        let options = HexDumpOptions { width: usize::MAX, annotate: false, ..HexDumpOptions::new() };
+        let dump = buffer.hexdump_with_options(&options);
+        assert_eq!(dump.lines().count(), 2);
+        assert!(dump.lines().nth(1).unwrap().starts_with("00000100  41 "));
+    }
+
+    //#endregion Hex dump tests
+
+    //#region Byte order tests
+
+    #[test]
+    fn read_little_endian() {
+        use super::ByteOrder::ByteOrder;
+
+        let mut buffer = Buffer::new_with_all_args(16, ByteOrder::LittleEndian, "utf-8", "\0");
+        buffer.write_u16(0x0102);
+        buffer.write_f32(1.5);
+        assert_eq!(buffer.get_data(), vec![0x02, 0x01, 0x00, 0x00, 0xC0, 0x3F]);
+        assert_eq!(buffer.read_u16(), Some(0x0102));
+        assert_eq!(buffer.read_f32(), Some(1.5));
+
+        buffer.set_byte_order(ByteOrder::BigEndian);
+        buffer.write_u16(0x0102);
+        assert_eq!(buffer.read_u16(), Some(0x0102));
+        assert_eq!(buffer.get_byte_order(), ByteOrder::BigEndian);
+    }
+
+    //#endregion Byte order tests
+
+    //#region Schema tests
+
+    #[test]
+    fn schema_decode_and_encode() {
+        use super::Schema::Schema;
+        use super::Value::Value;
+
+        let schema = Schema::parse("
+            # A made up packet
+            id: u16le
+            name: string(prefix=u8)
+            count: varint
+            points: array(struct { x: i32; y: i32 }, count=count)
+            tail: bytes(len=2)
+        ").unwrap();
+
+        let mut buffer = Buffer::new();
+        buffer.write_bytes(&[0x34, 0x12]);
+        buffer.write_u8(3);
+        buffer.write_bytes(b"abc");
+        buffer.write_varint(2);
+        buffer.write_i32(1);
+        buffer.write_i32(-2);
+        buffer.write_i32(3);
+        buffer.write_i32(-4);
+        buffer.write_bytes(&[0xAA, 0xBB]);
+
+        let value = schema.decode(&mut buffer).unwrap();
+        assert_eq!(value.get("id"), Some(&Value::UInt(0x1234)));
+        assert_eq!(value.get("name"), Some(&Value::String("abc".to_string())));
+        assert_eq!(
+            value.to_json(),
+            r#"{"id":4660,"name":"abc","count":2,"points":[{"x":1,"y":-2},{"x":3,"y":-4}],"tail":[170,187]}"#
+        );
+
+        let mut encoded = Buffer::new();
+        schema.encode(&Value::from_json(&value.to_json()).unwrap(), &mut encoded).unwrap();
+        assert_eq!(encoded.get_data(), buffer.get_data());
+    }
+
+    #[test]
+    fn schema_from_json() {
+        use super::Schema::{FieldType, Length, Schema};
+        use super::ByteOrder::ByteOrder;
+
+        let schema = Schema::from_json(r#"{ "fields": [
+            { "name": "id", "type": "u16le" },
+            { "name": "name", "type": "string", "prefix": "u8" },
+            { "name": "values", "type": "array", "of": "u32be", "count": 2 },
+            { "name": "inner", "type": "struct", "fields": [{ "name": "flag", "type": "bool" }] }
+        ]}"#).unwrap();
+
+        let expected = Schema::new()
+            .field("id", FieldType::U16(Some(ByteOrder::LittleEndian)))
+            .field("name", FieldType::String(Length::Prefix(Box::new(FieldType::U8))))
+            .field("values", FieldType::Array(Box::new(FieldType::U32(Some(ByteOrder::BigEndian))), Length::Fixed(2)))
+            .field("inner", FieldType::Struct(Schema::new().field("flag", FieldType::Bool)));
+        assert_eq!(schema, expected);
+        assert_eq!(Schema::parse("id: u16le; name: string(prefix=u8); values: array(u32be, count=2); inner: struct { flag: bool }"), Ok(expected));
+    }
+
+    #[test]
+    fn schema_errors() {
+        use super::Schema::Schema;
+        use super::Value::Value;
+
+        assert!(Schema::parse("id: u24").is_err());
+        assert!(Schema::parse("data: bytes").is_err());
+
+        let schema = Schema::parse("count: u8; items: array(struct { x: u16 }, count=count)").unwrap();
+        let mut buffer = Buffer::new();
+        buffer.write_bytes(&[0x02, 0x00, 0x01, 0x00]);
+        let error = schema.decode(&mut buffer).unwrap_err();
+        assert_eq!(error.to_string(), "items.1.x: unexpected end of buffer");
+
+        let value = Value::from_json(r#"{ "count": 3, "items": [{ "x": 1 }] }"#).unwrap();
+        assert!(schema.encode(&value, &mut Buffer::new()).is_err());
+        let value = Value::from_json(r#"{ "count": 1, "items": [{ "x": 70000 }] }"#).unwrap();
+        assert_eq!(schema.encode(&value, &mut Buffer::new()).unwrap_err().path(), "items.0.x");
+
+        // A failed encode writes nothing, even after earlier fields fit
+        let schema = Schema::parse("id: u16; name: string(prefix=u8); x: u8").unwrap();
+        let value = Value::from_json(r#"{ "id": 1, "name": "hi", "x": 300 }"#).unwrap();
+        let mut buffer = Buffer::new();
+        assert_eq!(schema.encode(&value, &mut buffer).unwrap_err().path(), "x");
+        assert!(buffer.is_empty());
+
+        // Deep nesting is rejected instead of overflowing the stack
+        let text = "a: struct {".repeat(200_000);
+        assert_eq!(Schema::parse(&text).unwrap_err().message(), "nesting depth exceeds the limit");
+        let text = "array(item=".repeat(200_000);
+        assert!(super::Schema::FieldType::parse(&text).is_err());
+        let text = format!("{}b: u8{}", "a: struct { ".repeat(100), " }".repeat(100));
+        assert!(Schema::parse(&text).is_ok());
+    }
+
+    #[test]
+    fn value_json_round_trip() {
+        use super::Value::Value;
+
+        let text = r#"{"a":[1,-2,3.5,true,null],"b":"line\n\"quoted\" \u00e9"}"#;
+        let value = Value::from_json(text).unwrap();
+        assert_eq!(value.get("b"), Some(&Value::String("line\n\"quoted\" \u{e9}".to_string())));
+        assert_eq!(Value::from_json(&value.to_json()).unwrap(), value);
+        assert_eq!(Value::from_json(&value.to_json_pretty()).unwrap(), value);
+        assert!(Value::from_json("[1, 2").is_err());
+
+        assert_eq!(Value::from_json(r#""\ud83d\ude00""#).unwrap(), Value::String("\u{1F600}".to_string()));
+        assert!(Value::from_json(r#""\ud83d\u0041""#).is_err());
+        assert!(Value::from_json(r#""\ud83d\ud83d""#).is_err());
+        assert!(Value::from_json(r#""\ud83d""#).is_err());
+    }
+
+    #[test]
+    fn field_type_parse() {
+        use super::Schema::{FieldType, Length};
+        use super::ByteOrder::ByteOrder;
+        use super::Value::Value;
+
+        assert_eq!(FieldType::parse("u16le"), Ok(FieldType::U16(Some(ByteOrder::LittleEndian))));
+        assert_eq!(FieldType::parse("bytes(len=0x10)"), Ok(FieldType::Bytes(Length::Fixed(16))));
+        assert!(FieldType::parse("u16le u8").is_err());
+
+        let mut buffer = Buffer::new();
+        buffer.write_bytes(&[0x02, 0x68, 0x69]);
+        let value = FieldType::parse("string(prefix=u8)").unwrap().decode(&mut buffer, &[]);
+        assert_eq!(value, Ok(Value::String("hi".to_string())));
+    }
+
+    //#endregion Schema tests
+
+    //#region Decode limit tests
+
+    #[test]
+    fn read_bytes_overflowing_length() {
+        let mut buffer = Buffer::new();
+        buffer.write_bytes(&[0x01, 0x02]);
+        buffer.read_byte();
+        assert_eq!(buffer.read_bytes(usize::MAX), None);
+        assert_eq!(buffer.read_bytes(1), Some(vec![0x02]));
+    }
+
+    #[test]
+    fn read_varint_too_long() {
+        let mut buffer = Buffer::new();
+        buffer.write_bytes(&[0xFF; 11]);
+        assert_eq!(buffer.read_varint(), None);
+    }
+
+    #[test]
+    fn decode_limits_strings_and_bytes() {
+        use super::DecodeLimits::DecodeLimits;
+
+        let mut buffer = Buffer::new();
+        buffer.set_decode_limits(DecodeLimits { max_string_length: 4, max_bytes_length: 2, ..DecodeLimits::new() });
+        buffer.write_u32(0xFFFF_FFFF);
+        assert_eq!(buffer.read_string_with_length(), None);
+
+        let mut buffer = Buffer::new();
+        buffer.set_decode_limits(DecodeLimits { max_string_length: 4, max_bytes_length: 2, ..DecodeLimits::new() });
+        buffer.write_string("Hello");
+        buffer.write_bytes(&[0x01, 0x02, 0x03]);
+        assert_eq!(buffer.read_string(), None);
+        buffer.set_decode_limits(DecodeLimits { max_bytes_length: 2, ..DecodeLimits::new() });
+        assert_eq!(buffer.read_string(), Some("Hello".to_string()));
+        assert_eq!(buffer.read_bytes(3), None);
+        assert_eq!(buffer.read_bytes(2), Some(vec![0x01, 0x02]));
+    }
+
+    #[test]
+    fn decode_limits_total_allocation() {
+        use super::DecodeLimits::DecodeLimits;
+
+        let mut buffer = Buffer::new();
+        buffer.set_decode_limits(DecodeLimits { max_total_allocation: 4, ..DecodeLimits::new() });
+        buffer.write_bytes(&[0x00; 8]);
+        assert!(buffer.read_bytes(3).is_some());
+        assert!(buffer.read_bytes(3).is_none());
+        buffer.reset_allocation();
+        assert!(buffer.read_bytes(3).is_some());
+    }
+
+    #[test]
+    fn decode_limits_schema() {
+        use super::DecodeLimits::DecodeLimits;
+        use super::Schema::Schema;
+
+        let schema = Schema::parse("items: array(struct {}, prefix=u32)").unwrap();
+        let mut buffer = Buffer::new();
+        buffer.set_decode_limits(DecodeLimits { max_collection_count: 1000, ..DecodeLimits::new() });
+        buffer.write_u32(0xFFFF_FFFF);
+        let error = schema.decode(&mut buffer).unwrap_err();
+        assert_eq!(error.to_string(), "items: array count 4294967295 exceeds the limit of 1000");
+
+        let schema = Schema::parse("a: struct { b: struct { c: struct { d: u8 } } }").unwrap();
+        let mut buffer = Buffer::new();
+        buffer.set_decode_limits(DecodeLimits { max_nesting_depth: 2, ..DecodeLimits::new() });
+        buffer.write_u8(1);
+        assert!(schema.decode(&mut buffer).is_err());
+        buffer.set_decode_limits(DecodeLimits::new());
+        assert!(schema.decode(&mut buffer).is_ok());
+    }
+
+    #[cfg(feature = "deflate")]
+    #[test]
+    fn decode_limits_decompression() {
+        use super::Compression::Codec;
+        use super::DecodeLimits::DecodeLimits;
+
+        let mut body = Buffer::new();
+        body.write_bytes(&[0x00; 4096]);
+        let mut packet = Buffer::new();
+        let written = body.compress_into(&mut packet, Codec::Zlib).unwrap();
+        packet.set_decode_limits(DecodeLimits { max_bytes_length: 1024, ..DecodeLimits::new() });
+        assert!(packet.read_compressed(written, Codec::Zlib).is_none());
+        packet.set_decode_limits(DecodeLimits::new());
+        assert!(packet.read_compressed(written, Codec::Zlib).is_some());
+    }
+
+    //#endregion Decode limit tests
+
+    //#region std::io tests
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_read_and_write() {
+        use std::io::Read;
+
+        let mut buffer = Buffer::new();
+        buffer.write_all(b"Hello, world!").unwrap();
+        buffer.read_u8();
+
+        let mut out = String::new();
+        buffer.read_to_string(&mut out).unwrap();
+        assert_eq!(out, "ello, world!");
+        assert_eq!(buffer.read_u8(), None);
+    }
+
+    //#endregion std::io tests
+
+    //#region Storage tests
+
+    #[test]
+    fn read_borrowed_slice() {
+        let data = [0x01, 0x02, 0x03, 0x04, 0x05];
+        let mut buffer = Buffer::wrap(&data[..]);
+        assert_eq!(buffer.len(), 5);
+        assert_eq!(buffer.read_u16(), Some(0x0102));
+        assert_eq!(buffer.read_bytes(3), Some(vec![0x03, 0x04, 0x05]));
+        assert_eq!(buffer.read_byte(), None);
+    }
+
+    #[test]
+    fn write_mutable_slice() {
+        let mut data = [0xFF; 5];
+        let mut buffer = Buffer::wrap_empty(&mut data[..]);
+        assert_eq!(buffer.write_u32(0x01020304), Some(()));
+        assert_eq!(buffer.write_u16(0x0506), None);
+        assert_eq!(buffer.write_string("a"), None);
+        assert_eq!(buffer.len(), 4);
+        assert_eq!(buffer.read_u32(), Some(0x01020304));
+        assert_eq!(buffer.write_u8(0x05), Some(()));
+        assert_eq!(data, [0x01, 0x02, 0x03, 0x04, 0x05]);
+    }
+
+    #[test]
+    fn write_fixed_array() {
+        let mut buffer = Buffer::wrap_empty([0; 4]);
+        buffer.write_string_with_length("").unwrap();
+        assert_eq!(buffer.write_byte(0x01), None);
+        assert_eq!(buffer.into_storage(), [0; 4]);
+
+        let mut buffer = Buffer::wrap([0x00, 0x00, 0x00, 0x01]);
+        assert_eq!(buffer.read_u32(), Some(1));
+    }
+
+    #[test]
+    fn wrap_vec() {
+        let mut buffer = Buffer::wrap(vec![0x01, 0x02]);
+        buffer.write_u8(0x03);
+        assert_eq!(buffer.as_slice(), &[0x01, 0x02, 0x03]);
+        assert_eq!(buffer.read_bytes(3), Some(vec![0x01, 0x02, 0x03]));
+        assert_eq!(Buffer::wrap_empty(vec![0x01]).len(), 0);
+
+        // The vector is the only record of the length, even when it is changed directly
+        buffer.data.push(0x04);
+        buffer.extend([0x05]);
+        assert_eq!(buffer.len(), 5);
+        assert_eq!(core::mem::size_of::<<Vec<u8> as super::Storage::Storage>::Length>(), 0);
+    }
+
+    #[test]
+    fn schema_over_storage() {
+        use super::Schema::Schema;
+        use super::Value::Value;
+
+        let schema = Schema::parse("id: u16; name: string(prefix=u8)").unwrap();
+        let data = [0x00, 0x07, 0x02, b'h', b'i'];
+        let value = schema.decode(&mut Buffer::wrap(&data[..])).unwrap();
+        assert_eq!(value.get("id"), Some(&Value::UInt(7)));
+
+        // The record doesn't fit, so none of it is written
+        let mut out = [0; 4];
+        let mut buffer = Buffer::wrap_empty(&mut out[..]);
+        assert_eq!(schema.encode(&value, &mut buffer).unwrap_err().to_string(), "buffer is full");
+        assert!(buffer.is_empty());
+    }
+
+    //#endregion Storage tests
+
+    //#region Position tests
+
+    #[test]
+    fn peek_and_set_position() {
+        let mut buffer = Buffer::wrap(vec![0x01, 0x02, 0x03]);
+        assert_eq!(buffer.peek_byte(), Some(0x01));
+        assert_eq!(buffer.peek_bytes(2), Some(&[0x01, 0x02][..]));
+        assert_eq!(buffer.peek_bytes(4), None);
+        assert_eq!(buffer.get_position(), 0);
+
+        assert_eq!(buffer.set_position(2), Some(()));
+        assert_eq!(buffer.read_byte(), Some(0x03));
+        assert_eq!(buffer.peek_byte(), None);
+        assert_eq!(buffer.set_position(4), None);
+        assert_eq!(buffer.get_position(), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn io_seek() {
+        use std::io::{Seek, SeekFrom};
+
+        let mut buffer = Buffer::wrap(&[0x01, 0x02, 0x03, 0x04][..]);
+        assert_eq!(buffer.seek(SeekFrom::End(-1)).unwrap(), 3);
+        assert_eq!(buffer.read_byte(), Some(0x04));
+        assert_eq!(buffer.seek(SeekFrom::Current(-3)).unwrap(), 1);
+        assert_eq!(buffer.read_byte(), Some(0x02));
+        assert!(buffer.seek(SeekFrom::Current(-3)).is_err());
+        assert!(buffer.seek(SeekFrom::Start(5)).is_err());
+        assert_eq!(buffer.get_position(), 2);
+    }
+
+    //#endregion Position tests
+
+    //#region File tests
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn file_round_trip() {
+        let path = std::env::temp_dir().join(format!("muscleman-file-{}.bin", std::process::id()));
+        let mut buffer = Buffer::new();
+        buffer.write_u32(0xCAFEBABE);
+        buffer.write_string("hello");
+        buffer.write_to_file(&path).unwrap();
+
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(buffer.read_u32(), Some(0xCAFEBABE));
+        assert_eq!(buffer.read_string(), Some("hello".to_string()));
+    }
+
+    #[test]
+    #[cfg(feature = "mmap")]
+    fn mmap_reads() {
+        let path = std::env::temp_dir().join(format!("muscleman-mmap-{}.bin", std::process::id()));
+        std::fs::write(&path, [0x00, 0x00, 0x00, 0x2A, b'h', b'i', 0x00]).unwrap();
+
+        // SAFETY: the file is only written before it is mapped, and removed after the buffer is dropped
+        let mut buffer = unsafe { Buffer::open_mmap(&path) }.unwrap();
+        assert_eq!(buffer.len(), 7);
+        assert_eq!(buffer.read_u32(), Some(42));
+        assert_eq!(buffer.peek_bytes(2), Some(&b"hi"[..]));
+        assert_eq!(buffer.read_string(), Some("hi".to_string()));
+        buffer.set_position(0).unwrap();
+        assert_eq!(buffer.read_u8(), Some(0));
+        drop(buffer);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    //#endregion File tests
+
+    //#region Numeric tests
+
+    #[test]
+    fn f16_conversions() {
+        use super::Numeric::{f16_to_f32, f32_to_f16};
+
+        let cases = [
+            (0x0000, 0.0),
+            (0x3C00, 1.0),
+            (0xC000, -2.0),
+            (0x3555, 0.33325195),
+            (0x7BFF, 65504.0),
+            (0x0400, 6.1035156e-5),
+            (0x0001, 5.9604645e-8),
+            (0x7C00, f32::INFINITY),
+            (0xFC00, f32::NEG_INFINITY),
+        ];
+        for (half, value) in cases {
+            assert_eq!(f16_to_f32(half), value);
+            assert_eq!(f32_to_f16(value), half);
+        }
+
+        assert_eq!(f32_to_f16(-0.0), 0x8000);
+        assert_eq!(f32_to_f16(65520.0), 0x7C00);
+        assert_eq!(f32_to_f16(1e-10), 0x0000);
+        assert_eq!(f32_to_f16(1.0 + 1.0 / 2048.0), 0x3C00);
+        assert_eq!(f32_to_f16(1.0 + 3.0 / 2048.0), 0x3C02);
+        assert!(f16_to_f32(f32_to_f16(f32::NAN)).is_nan());
+    }
+
+    #[test]
+    fn half_precision_reads_and_writes() {
+        use super::ByteOrder::ByteOrder;
+
+        let mut buffer = Buffer::new();
+        buffer.write_f16(1.5);
+        buffer.write_bf16(-3.140625);
+        buffer.set_byte_order(ByteOrder::LittleEndian);
+        buffer.write_f16(0.1);
+        buffer.write_bf16(1e30);
+        assert_eq!(buffer.as_slice(), [0x3E, 0x00, 0xC0, 0x49, 0x66, 0x2E, 0x4A, 0x71]);
+
+        buffer.set_byte_order(ByteOrder::BigEndian);
+        assert_eq!(buffer.read_f16(), Some(1.5));
+        assert_eq!(buffer.read_bf16(), Some(-3.140625));
+        buffer.set_byte_order(ByteOrder::LittleEndian);
+        assert_eq!(buffer.read_f16(), Some(0.099975586));
+        assert_eq!(buffer.read_bf16(), Some(f32::from_bits(0x714A_0000)));
+        assert_eq!(buffer.read_f16(), None);
+    }
+
+    #[test]
+    fn fixed_point_reads_and_writes() {
+        use super::ByteOrder::ByteOrder;
+
+        let mut buffer = Buffer::new();
+        buffer.write_fixed::<16, 16>(-1.5);
+        buffer.write_fixed::<8, 8>(2.25);
+        buffer.write_ufixed::<8, 8>(255.99609375);
+        buffer.set_byte_order(ByteOrder::LittleEndian);
+        buffer.write_fixed::<16, 16>(0.1);
+        assert_eq!(buffer.as_slice()[..8], [0xFF, 0xFE, 0x80, 0x00, 0x02, 0x40, 0xFF, 0xFF]);
+
+        assert_eq!(buffer.write_fixed::<8, 8>(128.0), None);
+        assert_eq!(buffer.write_fixed::<8, 8>(-128.0), Some(()));
+        assert_eq!(buffer.write_ufixed::<8, 8>(-0.5), None);
+        assert_eq!(buffer.write_fixed::<16, 16>(f64::NAN), None);
+
+        buffer.set_byte_order(ByteOrder::BigEndian);
+        assert_eq!(buffer.read_fixed::<16, 16>(), Some(-1.5));
+        assert_eq!(buffer.read_fixed::<8, 8>(), Some(2.25));
+        assert_eq!(buffer.read_ufixed::<8, 8>(), Some(255.99609375));
+        buffer.set_byte_order(ByteOrder::LittleEndian);
+        assert_eq!(buffer.read_fixed::<16, 16>(), Some(6554.0 / 65536.0));
+        assert_eq!(buffer.read_fixed::<8, 8>(), Some(-128.0));
+        assert_eq!(buffer.read_fixed::<2, 30>(), None);
+    }
+
+    //#endregion Numeric tests
+
+    //#region Bulk tests
+
+    #[test]
+    fn bulk_reads() {
+        use super::ByteOrder::ByteOrder;
+
+        let mut buffer = Buffer::wrap(vec![0x00, 0x01, 0x00, 0x02, 0x03, 0x00, 0x04, 0x00, 0xFF]);
+        let mut out = [0; 2];
+        assert_eq!(buffer.read_u16_into(&mut out), Some(()));
+        assert_eq!(out, [1, 2]);
+
+        buffer.set_byte_order(ByteOrder::LittleEndian);
+        assert_eq!(buffer.read_i16_vec(3), None);
+        assert_eq!(buffer.get_position(), 4);
+        assert_eq!(buffer.read_i16_vec(2), Some(vec![3, 4]));
+        assert_eq!(buffer.read_i8_vec(1), Some(vec![-1]));
+        assert_eq!(buffer.read_u64_vec(usize::MAX), None);
+        assert_eq!(buffer.read_f32_vec(0), Some(vec![]));
+    }
+
+    #[test]
+    fn bulk_round_trip() {
+        use super::ByteOrder::ByteOrder;
+
+        let samples: Vec<i16> = (0..1000).map(|i| (i * 37 - 18000) as i16).collect();
+        let floats = [1.5f64, -0.25, f64::INFINITY];
+        for byte_order in [ByteOrder::BigEndian, ByteOrder::LittleEndian] {
+            let mut buffer = Buffer::new();
+            buffer.set_byte_order(byte_order);
+            buffer.write_i16_slice(&samples);
+            buffer.write_f64_slice(&floats);
+            buffer.write_u32_slice(&[0x01020304]);
+
+            let mut single = Buffer::wrap(buffer.get_data());
+            single.set_byte_order(byte_order);
+            assert_eq!(single.read_i16(), Some(samples[0]));
+
+            assert_eq!(buffer.read_i16_vec(1000), Some(samples.clone()));
+            assert_eq!(buffer.read_f64_vec(3), Some(floats.to_vec()));
+            let mut out = [0u8; 4];
+            buffer.read_u8_into(&mut out).unwrap();
+            let expected = match byte_order {
+                ByteOrder::BigEndian => [0x01, 0x02, 0x03, 0x04],
+                ByteOrder::LittleEndian => [0x04, 0x03, 0x02, 0x01],
+            };
+            assert_eq!(out, expected);
+        }
+    }
+
+    #[test]
+    fn bulk_limits() {
+        use super::DecodeLimits::DecodeLimits;
+
+        let mut buffer = Buffer::wrap(vec![0; 16]);
+        buffer.set_decode_limits(DecodeLimits { max_collection_count: 3, ..DecodeLimits::new() });
+        assert_eq!(buffer.read_u32_vec(4), None);
+        assert_eq!(buffer.read_u32_vec(3), Some(vec![0; 3]));
+
+        let mut out = [0; 2];
+        let mut buffer = Buffer::wrap_empty(&mut out[..]);
+        assert_eq!(buffer.write_u8_slice(&[1, 2, 3]), None);
+        assert_eq!(buffer.len(), 0);
+    }
+
+    //#endregion Bulk tests
+
+    //#region Hot path tests
+
+    #[test]
+    fn read_string_maps_bytes_to_chars() {
+        let mut buffer = Buffer::wrap(vec![b'a', 0xE9, 0x00, b'b', b'c']);
+        assert_eq!(buffer.read_string(), Some("a\u{e9}".to_string()));
+        // An unterminated string might still be arriving
+        assert_eq!(buffer.read_string(), None);
+        assert_eq!(buffer.get_position(), 3);
+
+        let mut buffer = Buffer::wrap(vec![0x00, 0x00, 0x00, 0x02, 0xFF, b'z']);
+        assert_eq!(buffer.read_string_with_length(), Some("\u{ff}z".to_string()));
+    }
+
+    //#endregion Hot path tests
+
+    //#region Protobuf tests
+
+    #[test]
+    fn protobuf_encoding() {
+        use super::ByteOrder::ByteOrder;
+        use super::Protobuf::WireType;
+
+        let mut buffer = Buffer::new();
+        buffer.set_byte_order(ByteOrder::BigEndian);
+        buffer.write_tag(1, WireType::Varint);
+        buffer.write_varint(150);
+        buffer.write_tag(2, WireType::LengthDelimited);
+        buffer.write_length_delimited(b"testing");
+        buffer.write_tag(4, WireType::LengthDelimited);
+        buffer.write_packed_varints(&[3, 270, 86942]);
+        buffer.write_tag(5, WireType::Fixed32);
+        buffer.write_fixed32(1);
+        buffer.write_tag(6, WireType::Varint);
+        buffer.write_varint(-1);
+        buffer.write_tag(7, WireType::Varint);
+        buffer.write_sint32(-2);
+        assert_eq!(buffer.as_slice(), [
+            0x08, 0x96, 0x01,
+            0x12, 0x07, b't', b'e', b's', b't', b'i', b'n', b'g',
+            0x22, 0x06, 0x03, 0x8E, 0x02, 0x9E, 0xA7, 0x05,
+            0x2D, 0x01, 0x00, 0x00, 0x00,
+            0x30, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01,
+            0x38, 0x03,
+        ]);
+
+        assert_eq!(buffer.read_tag(), Some((1, WireType::Varint)));
+        assert_eq!(buffer.read_varint(), Some(150));
+        assert_eq!(buffer.read_tag(), Some((2, WireType::LengthDelimited)));
+        assert_eq!(buffer.read_length_delimited_string(), Some("testing".to_string()));
+        assert_eq!(buffer.read_tag(), Some((4, WireType::LengthDelimited)));
+        assert_eq!(buffer.read_packed_varints(), Some(vec![3, 270, 86942]));
+        assert_eq!(buffer.read_tag(), Some((5, WireType::Fixed32)));
+        assert_eq!(buffer.read_fixed32(), Some(1));
+        assert_eq!(buffer.read_tag(), Some((6, WireType::Varint)));
+        assert_eq!(buffer.read_varint(), Some(-1));
+        assert_eq!(buffer.read_tag(), Some((7, WireType::Varint)));
+        assert_eq!(buffer.read_sint32(), Some(-2));
+    }
+
+    #[test]
+    fn protobuf_submessages_and_packed() {
+        use super::Protobuf::WireType;
+
+        let mut buffer = Buffer::new();
+        buffer.write_tag(3, WireType::LengthDelimited);
+        buffer.write_submessage(|message| {
+            message.write_tag(1, WireType::Fixed64)?;
+            message.write_sfixed64(-5)?;
+            message.write_tag(2, WireType::Varint)?;
+            message.write_sint64(i64::MIN)
+        });
+        buffer.write_packed_fixed32(&[1, 0xDEADBEEF]);
+        buffer.write_packed_fixed64(&[u64::MAX]);
+
+        assert_eq!(buffer.read_tag(), Some((3, WireType::LengthDelimited)));
+        let mut message = buffer.read_submessage().unwrap();
+        assert_eq!(message.read_tag(), Some((1, WireType::Fixed64)));
+        assert_eq!(message.read_sfixed64(), Some(-5));
+        assert_eq!(message.read_tag(), Some((2, WireType::Varint)));
+        assert_eq!(message.read_sint64(), Some(i64::MIN));
+        assert_eq!(message.read_tag(), None);
+
+        assert_eq!(buffer.read_packed_fixed32(), Some(vec![1, 0xDEADBEEF]));
+        assert_eq!(buffer.get_byte_order(), super::ByteOrder::ByteOrder::BigEndian);
+        assert_eq!(buffer.read_packed_fixed64(), Some(vec![u64::MAX]));
+        assert_eq!(buffer.remaining(), 0);
+
+        // Submessages are read in the byte order they were written in
+        let mut buffer = Buffer::new();
+        buffer.set_byte_order(super::ByteOrder::ByteOrder::LittleEndian);
+        buffer.write_submessage(|message| message.write_u32(0x01020304));
+        assert_eq!(buffer.as_slice(), [0x04, 0x04, 0x03, 0x02, 0x01]);
+        assert_eq!(buffer.read_submessage().unwrap().read_u32(), Some(0x01020304));
+    }
+
+    #[test]
+    fn protobuf_submessage_allocation() {
+        use super::DecodeLimits::DecodeLimits;
+        use super::Protobuf::WireType;
+
+        let mut buffer = Buffer::new();
+        for _ in 0..4 {
+            buffer.write_submessage(|message| {
+                message.write_tag(1, WireType::LengthDelimited)?;
+                message.write_length_delimited(&[0xAA; 8])
+            });
+        }
+        buffer.set_decode_limits(DecodeLimits { max_total_allocation: 24, ..DecodeLimits::new() });
+
+        // Each submessage charges its 10 bytes, so the third goes past the limit
+        let mut message = buffer.read_submessage().unwrap();
+        message.read_tag();
+        assert_eq!(message.read_length_delimited(), Some(vec![0xAA; 8]));
+        let mut message = buffer.read_submessage().unwrap();
+        message.read_tag();
+        assert_eq!(message.read_length_delimited(), Some(vec![0xAA; 8]));
+        assert!(buffer.read_submessage().is_none());
+        assert_eq!(buffer.get_position(), 22);
+
+        // A varint running past the end of a packed field
+        let mut buffer = Buffer::wrap(vec![0x01, 0x80, 0x01]);
+        assert_eq!(buffer.read_packed_varints(), None);
+        assert_eq!(buffer.get_position(), 0);
+    }
+
+    #[test]
+    fn protobuf_skip_field() {
+        use super::Protobuf::WireType;
+
+        let mut buffer = Buffer::new();
+        buffer.write_tag(1, WireType::Varint);
+        buffer.write_varint(300);
+        buffer.write_tag(2, WireType::Fixed64);
+        buffer.write_fixed64(7);
+        buffer.write_tag(3, WireType::StartGroup);
+        buffer.write_tag(1, WireType::LengthDelimited);
+        buffer.write_length_delimited(b"nested");
+        buffer.write_tag(3, WireType::EndGroup);
+        buffer.write_tag(4, WireType::Fixed32);
+        buffer.write_sfixed32(-9);
+
+        for _ in 0..3 {
+            let (field_number, wire_type) = buffer.read_tag().unwrap();
+            buffer.skip_field(field_number, wire_type).unwrap();
+        }
+        assert_eq!(buffer.read_tag(), Some((4, WireType::Fixed32)));
+        assert_eq!(buffer.read_sfixed32(), Some(-9));
+        assert_eq!(buffer.skip_field(4, WireType::EndGroup), None);
+
+        // A group has to end with its own field number
+        let mut buffer = Buffer::new();
+        buffer.write_tag(1, WireType::Varint);
+        buffer.write_varint(1);
+        buffer.write_tag(7, WireType::EndGroup);
+        assert_eq!(buffer.skip_field(5, WireType::StartGroup), None);
+        assert_eq!(buffer.get_position(), 0);
+
+        assert_eq!(Buffer::wrap(vec![0x00]).read_tag(), None);
+        assert_eq!(Buffer::wrap(vec![0x0E]).read_tag(), None);
+        assert_eq!(Buffer::new().write_tag(0, WireType::Varint), None);
+        assert_eq!(Buffer::wrap(vec![0x05, 0x01]).read_length_delimited(), None);
+    }
+
+    //#endregion Protobuf tests
+
+    //#region MessagePack tests
+
+    #[test]
+    fn msgpack_scalars() {
+        use super::ByteOrder::ByteOrder;
+
+        let mut buffer = Buffer::new();
+        buffer.set_byte_order(ByteOrder::LittleEndian);
+        buffer.write_msgpack_nil();
+        buffer.write_msgpack_bool(true);
+        buffer.write_msgpack_int(-1);
+        buffer.write_msgpack_int(-33);
+        buffer.write_msgpack_uint(200);
+        buffer.write_msgpack_int(70000);
+        buffer.write_msgpack_int(i64::MIN);
+        buffer.write_msgpack_f32(1.5);
+        buffer.write_msgpack_f64(-2.0);
+        buffer.write_msgpack_str("hi");
+        buffer.write_msgpack_bin(&[1, 2]);
+        buffer.write_msgpack_ext(-1, &[0; 4]);
+        buffer.write_msgpack_ext(5, &[7; 3]);
+        assert_eq!(buffer.as_slice(), [
+            0xC0, 0xC3, 0xFF, 0xD0, 0xDF, 0xCC, 0xC8,
+            0xCE, 0x00, 0x01, 0x11, 0x70,
+            0xD3, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xCA, 0x3F, 0xC0, 0x00, 0x00,
+            0xCB, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xA2, b'h', b'i',
+            0xC4, 0x02, 0x01, 0x02,
+            0xD6, 0xFF, 0x00, 0x00, 0x00, 0x00,
+            0xC7, 0x03, 0x05, 0x07, 0x07, 0x07,
+        ]);
+
+        assert_eq!(buffer.read_msgpack_bool(), None);
+        assert_eq!(buffer.read_msgpack_nil(), Some(()));
+        assert_eq!(buffer.read_msgpack_bool(), Some(true));
+        assert_eq!(buffer.read_msgpack_uint(), None);
+        assert_eq!(buffer.read_msgpack_int(), Some(-1));
+        assert_eq!(buffer.read_msgpack_int(), Some(-33));
+        assert_eq!(buffer.read_msgpack_uint(), Some(200));
+        assert_eq!(buffer.read_msgpack_uint(), Some(70000));
+        assert_eq!(buffer.read_msgpack_int(), Some(i64::MIN));
+        assert_eq!(buffer.read_msgpack_f64(), Some(1.5));
+        assert_eq!(buffer.read_msgpack_f32(), None);
+        assert_eq!(buffer.read_msgpack_f64(), Some(-2.0));
+        assert_eq!(buffer.read_msgpack_bin(), None);
+        assert_eq!(buffer.read_msgpack_str(), Some("hi".to_string()));
+        assert_eq!(buffer.read_msgpack_bin(), Some(vec![1, 2]));
+        assert_eq!(buffer.read_msgpack_ext(), Some((-1, vec![0; 4])));
+        assert_eq!(buffer.read_msgpack_ext(), Some((5, vec![7; 3])));
+        assert_eq!(buffer.remaining(), 0);
+    }
+
+    #[test]
+    fn msgpack_values() {
+        use super::Value::Value;
+
+        let value = Value::from_json(r#"{"compact": true, "schema": 0, "items": [1, -2, 3.5, null, "x"], "long": "abcdefghijklmnopqrstuvwxyz0123456789"}"#).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.write_msgpack_value(&value);
+        assert_eq!(buffer.as_slice()[..10], [0x84, 0xA7, b'c', b'o', b'm', b'p', b'a', b'c', b't', 0xC3]);
+        assert_eq!(buffer.read_msgpack_value(), Some(value));
+
+        let map = Value::Map(vec![(Value::UInt(1), Value::Bytes(vec![0xAB])), (Value::Null, Value::Ext(3, vec![]))]);
+        buffer.write_msgpack_value(&map);
+        assert_eq!(buffer.read_msgpack_value(), Some(map.clone()));
+        assert_eq!(map.to_json(), r#"[[1,[171]],[null,{"type":3,"data":[]}]]"#);
+
+        let mut buffer = Buffer::wrap(vec![0xDD, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(buffer.read_msgpack_value(), None);
+        assert_eq!(Buffer::wrap(vec![0xC1]).read_msgpack_value(), None);
+    }
+
+    #[test]
+    fn msgpack_limits() {
+        use super::DecodeLimits::DecodeLimits;
+
+        let mut buffer = Buffer::new();
+        for _ in 0..200 {
+            buffer.write_msgpack_array_len(1);
+        }
+        buffer.write_msgpack_nil();
+        assert_eq!(buffer.read_msgpack_value(), None);
+
+        let mut buffer = Buffer::new();
+        buffer.write_msgpack_array_len(3);
+        buffer.set_decode_limits(DecodeLimits { max_collection_count: 2, ..DecodeLimits::new() });
+        assert_eq!(buffer.read_msgpack_array_len(), None);
+
+        let mut out = [0; 3];
+        let mut buffer = Buffer::wrap_empty(&mut out[..]);
+        assert_eq!(buffer.write_msgpack_str("abc"), None);
+        assert_eq!(buffer.write_msgpack_uint(0x1234), Some(()));
+        assert_eq!(buffer.as_slice(), [0xCD, 0x12, 0x34]);
+    }
+
+    //#endregion MessagePack tests
+
+    //#region CBOR tests
+
+    #[test]
+    fn cbor_encoding() {
+        use super::ByteOrder::ByteOrder;
+
+        let mut buffer = Buffer::new();
+        buffer.set_byte_order(ByteOrder::LittleEndian);
+        buffer.write_cbor_uint(10);
+        buffer.write_cbor_uint(1000);
+        buffer.write_cbor_int(-1000);
+        buffer.write_cbor_int(i64::MIN);
+        buffer.write_cbor_text("IETF");
+        buffer.write_cbor_bytes(&[1, 2]);
+        buffer.write_cbor_float(1.5);
+        buffer.write_cbor_float(100000.0);
+        buffer.write_cbor_float(1.1);
+        buffer.write_cbor_float(f64::NAN);
+        buffer.write_cbor_simple(255);
+        buffer.write_cbor_tag(1);
+        buffer.write_cbor_uint(1363896240);
+        assert_eq!(buffer.as_slice(), [
+            0x0A,
+            0x19, 0x03, 0xE8,
+            0x39, 0x03, 0xE7,
+            0x3B, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0x64, b'I', b'E', b'T', b'F',
+            0x42, 0x01, 0x02,
+            0xF9, 0x3E, 0x00,
+            0xFA, 0x47, 0xC3, 0x50, 0x00,
+            0xFB, 0x3F, 0xF1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9A,
+            0xF9, 0x7E, 0x00,
+            0xF8, 0xFF,
+            0xC1, 0x1A, 0x51, 0x4B, 0x67, 0xB0,
+        ]);
+        assert_eq!(buffer.write_cbor_simple(24), None);
+    }
+
+    #[test]
+    fn cbor_events() {
+        use super::Cbor::CborEvent;
+
+        // {_ "Fun": true, "Amt": -2}, then (_ h'0102', h'03'), then [_ ] and undefined
+        let mut buffer = Buffer::wrap(vec![
+            0xBF, 0x63, b'F', b'u', b'n', 0xF5, 0x63, b'A', b'm', b't', 0x21, 0xFF,
+            0x5F, 0x42, 0x01, 0x02, 0x41, 0x03, 0xFF,
+            0x9F, 0xFF, 0xF7, 0xFC,
+        ]);
+        let events = [
+            CborEvent::Map(None),
+            CborEvent::Text("Fun".to_string()),
+            CborEvent::Bool(true),
+            CborEvent::Text("Amt".to_string()),
+            CborEvent::NegInt(1),
+            CborEvent::Break,
+            CborEvent::BytesStart,
+            CborEvent::Bytes(vec![1, 2]),
+            CborEvent::Bytes(vec![3]),
+            CborEvent::Break,
+            CborEvent::Array(None),
+            CborEvent::Break,
+            CborEvent::Undefined,
+        ];
+        for event in events {
+            assert_eq!(buffer.read_cbor_event(), Some(event));
+        }
+        assert_eq!(buffer.read_cbor_event(), None);
+        assert_eq!(Buffer::wrap(vec![0xF8, 0x10]).read_cbor_event(), None);
+        assert_eq!(Buffer::wrap(vec![0x1F]).read_cbor_event(), None);
+    }
+
+    #[test]
+    fn cbor_values() {
+        use super::Value::Value;
+
+        let mut buffer = Buffer::wrap(vec![
+            0xBF, 0x63, b'F', b'u', b'n', 0xF5, 0x63, b'A', b'm', b't', 0x21, 0xFF,
+            0x7F, 0x62, b's', b't', 0x61, b'r', 0xFF,
+            0xA1, 0x01, 0xD8, 0x20, 0xF9, 0x3C, 0x00,
+        ]);
+        assert_eq!(buffer.read_cbor_value().unwrap().to_json(), r#"{"Fun":true,"Amt":-2}"#);
+        assert_eq!(buffer.read_cbor_value(), Some(Value::String("str".to_string())));
+        let map = buffer.read_cbor_value().unwrap();
+        assert_eq!(map, Value::Map(vec![(Value::UInt(1), Value::Tag(32, Box::new(Value::Float(1.0))))]));
+
+        let mut buffer = Buffer::new();
+        buffer.write_cbor_value(&map);
+        buffer.write_cbor_value(&Value::Simple(16));
+        assert_eq!(buffer.read_cbor_value(), Some(map));
+        assert_eq!(buffer.read_cbor_value(), Some(Value::Simple(16)));
+        assert_eq!(buffer.write_cbor_value(&Value::Ext(1, vec![])), None);
+
+        let mut buffer = Buffer::wrap(vec![0x3B, 0x80, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(buffer.read_cbor_value(), None);
+        assert_eq!(Buffer::wrap(vec![0x5F, 0x61, b'a', 0xFF]).read_cbor_value(), None);
+    }
+
+    #[test]
+    fn cbor_canonical() {
+        use super::Value::Value;
+
+        let value = Value::from_json(r#"{"b": [1.0, -0.5], "aa": null, "a": {"z": 1, "y": 2}}"#).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.write_cbor_value_canonical(&value);
+        assert_eq!(buffer.as_slice(), [
+            0xA3,
+            0x61, b'a', 0xA2, 0x61, b'y', 0x02, 0x61, b'z', 0x01,
+            0x61, b'b', 0x82, 0xF9, 0x3C, 0x00, 0xF9, 0xB8, 0x00,
+            0x62, b'a', b'a', 0xF6,
+        ]);
+
+        let mut ordered = Buffer::new();
+        ordered.write_cbor_value(&value);
+        assert_eq!(ordered.as_slice()[..3], [0xA3, 0x61, b'b']);
+        let decoded = buffer.read_cbor_value().unwrap();
+        assert_eq!(decoded.to_json(), r#"{"a":{"y":2,"z":1},"b":[1.0,-0.5],"aa":null}"#);
+    }
+
+    //#endregion CBOR tests
+
+    //#region BSON tests
+
+    #[test]
+    fn bson_round_trip() {
+        use super::Bson::BsonValue;
+
+        let mut buffer = Buffer::new();
+        buffer.write_bson_document(&[("hello".to_string(), BsonValue::String("world".to_string()))]);
+        assert_eq!(buffer.as_slice(), b"\x16\x00\x00\x00\x02hello\x00\x06\x00\x00\x00world\x00\x00");
+
+        let fields = vec![
+            ("double".to_string(), BsonValue::Double(1.5)),
+            ("document".to_string(), BsonValue::Document(vec![("null".to_string(), BsonValue::Null)])),
+            ("array".to_string(), BsonValue::Array(vec![BsonValue::Int32(1), BsonValue::Bool(true)])),
+            ("binary".to_string(), BsonValue::Binary(0x80, vec![1, 2, 3])),
+            ("undefined".to_string(), BsonValue::Undefined),
+            ("id".to_string(), BsonValue::ObjectId([7; 12])),
+            ("date".to_string(), BsonValue::DateTime(-1)),
+            ("regex".to_string(), BsonValue::Regex { pattern: "^a".to_string(), options: "i".to_string() }),
+            ("pointer".to_string(), BsonValue::DbPointer { namespace: "db.c".to_string(), id: [1; 12] }),
+            ("code".to_string(), BsonValue::JavaScript("f()".to_string())),
+            ("symbol".to_string(), BsonValue::Symbol("s".to_string())),
+            ("scoped".to_string(), BsonValue::JavaScriptWithScope {
+                code: "x".to_string(),
+                scope: vec![("x".to_string(), BsonValue::Int64(i64::MIN))],
+            }),
+            ("timestamp".to_string(), BsonValue::Timestamp { time: 2, increment: 1 }),
+            ("decimal".to_string(), BsonValue::Decimal128([9; 16])),
+            ("min".to_string(), BsonValue::MinKey),
+            ("max".to_string(), BsonValue::MaxKey),
+        ];
+        let mut buffer = Buffer::new();
+        buffer.write_u8(0xAA);
+        buffer.write_bson_document(&fields);
+        buffer.write_u8(0xBB);
+        buffer.read_u8();
+
+        let document = buffer.read_bson_document().unwrap();
+        assert_eq!(document.to_vec().unwrap(), fields);
+        assert_eq!(document.iter().count(), fields.len());
+        assert_eq!(buffer.read_u8(), Some(0xBB));
+
+        assert_eq!(buffer.write_bson_document(&[("a\0b".to_string(), BsonValue::Null)]), None);
+    }
+
+    #[test]
+    fn bson_validation() {
+        // Lengths past the end, or too short to hold a terminator
+        let mut buffer = Buffer::wrap(vec![0x10, 0, 0, 0, 0]);
+        assert_eq!(buffer.read_bson_document().unwrap_err().message(), "unexpected end of document");
+        assert_eq!(buffer.get_position(), 0);
+        assert!(Buffer::wrap(vec![4, 0, 0, 0, 0]).read_bson_document().is_err());
+        assert!(Buffer::wrap(vec![5, 0, 0, 0, 1]).read_bson_document().is_err());
+
+        // An embedded document whose length doesn't match its elements
+        let mut buffer = Buffer::wrap(vec![
+            0x11, 0, 0, 0,
+            0x03, b'd', 0, 0x09, 0, 0, 0, 0x0A, b'n', 0, 0,
+            0, 0,
+        ]);
+        let error = buffer.read_bson_document().unwrap().to_vec().unwrap_err();
+        assert_eq!(error.message(), "document length does not match its elements");
+
+        // A string without its terminator, then no more elements after the error
+        let mut buffer = Buffer::wrap(vec![0x0F, 0, 0, 0, 0x02, b's', 0, 0x02, 0, 0, 0, b'a', b'b', 0, 0]);
+        let mut elements = buffer.read_bson_document().unwrap().iter();
+        assert_eq!(elements.next().unwrap().unwrap_err().offset(), 13);
+        assert!(elements.next().is_none());
+    }
+
+    #[test]
+    fn bson_document_allocation() {
+        use super::Bson::BsonValue;
+        use super::DecodeLimits::DecodeLimits;
+
+        let mut bytes = Buffer::new();
+        bytes.write_bson_document(&[("name".to_string(), BsonValue::String("muscleman".to_string()))]);
+
+        // Finds how much decoding the document once charges
+        let mut buffer = Buffer::wrap(bytes.as_slice());
+        buffer.read_bson_document().unwrap().to_vec().unwrap();
+        let charge = buffer.allocated;
+        assert!(charge > 0);
+
+        // Every iteration adds to the buffer's count, so the third one is over the limit
+        let mut buffer = Buffer::wrap(bytes.as_slice());
+        buffer.set_decode_limits(DecodeLimits { max_total_allocation: 2 * charge, ..DecodeLimits::new() });
+        let document = buffer.read_bson_document().unwrap();
+        assert!(document.to_vec().is_ok());
+        assert!(document.iter().next().unwrap().is_ok());
+        assert_eq!(document.to_vec().unwrap_err().message(), "value exceeds the decode limits");
+        assert_eq!(buffer.allocated, 2 * charge);
+    }
+
+    //#endregion BSON tests
+
+    //#region Minecraft tests
+
+    #[test]
+    fn minecraft_types() {
+        use super::Minecraft::Position;
+
+        let mut buffer = Buffer::new();
+        buffer.write_mc_varint(25565);
+        buffer.write_mc_varint(-1);
+        buffer.write_mc_varlong(i64::MIN);
+        buffer.write_mc_position(Position { x: 18357644, y: 831, z: -20882616 });
+        buffer.write_mc_angle(-90.0);
+        assert_eq!(buffer.as_slice(), [
+            0xDD, 0xC7, 0x01,
+            0xFF, 0xFF, 0xFF, 0xFF, 0x0F,
+            0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01,
+            0x46, 0x07, 0x63, 0x2C, 0x15, 0xB4, 0x83, 0x3F,
+            0xC0,
+        ]);
+        assert_eq!(buffer.read_mc_varint(), Some(25565));
+        assert_eq!(buffer.read_mc_varint(), Some(-1));
+        assert_eq!(buffer.read_mc_varlong(), Some(i64::MIN));
+        assert_eq!(buffer.read_mc_position(), Some(Position { x: 18357644, y: 831, z: -20882616 }));
+        assert_eq!(buffer.read_mc_angle(), Some(270.0));
+        assert_eq!(Buffer::wrap(vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x01]).read_mc_varint(), None);
+        assert_eq!(buffer.write_mc_position(Position { x: 1 << 25, y: 0, z: 0 }), None);
+
+        buffer.write_mc_string("héllo", 5);
+        buffer.write_mc_identifier("minecraft:block/stone");
+        buffer.write_mc_uuid(0x0011_2233_4455_6677_8899_AABB_CCDD_EEFF);
+        buffer.write_mc_bitset(&[1, u64::MAX]);
+        buffer.write_mc_optional(Some(7), |buffer, value| buffer.write_u8(value));
+        buffer.write_mc_optional(None::<u8>, |buffer, value| buffer.write_u8(value));
+        assert_eq!(buffer.read_mc_string(4), None);
+        buffer.set_position(27);
+        assert_eq!(buffer.read_mc_string(5).as_deref(), Some("héllo"));
+        assert_eq!(buffer.read_mc_identifier().as_deref(), Some("minecraft:block/stone"));
+        assert_eq!(buffer.read_mc_uuid(), Some(0x0011_2233_4455_6677_8899_AABB_CCDD_EEFF));
+        assert_eq!(buffer.read_mc_bitset(), Some(vec![1, u64::MAX]));
+        assert_eq!(buffer.read_mc_optional(|buffer| buffer.read_u8()), Some(Some(7)));
+        assert_eq!(buffer.read_mc_optional(|buffer| buffer.read_u8()), Some(None));
+
+        assert_eq!(buffer.write_mc_string("long", 3), None);
+        assert_eq!(buffer.write_mc_identifier("Minecraft:Stone"), None);
+    }
+
+    #[test]
+    fn minecraft_packets() {
+        let mut buffer = Buffer::new();
+        buffer.write_mc_packet(0x00, &[1, 2, 3], None);
+        buffer.write_mc_packet(0x2A, &[4], Some(256));
+        assert_eq!(buffer.as_slice(), [0x04, 0x00, 1, 2, 3, 0x03, 0x00, 0x2A, 4]);
+
+        let (packet_id, mut packet) = buffer.read_mc_packet(None).unwrap();
+        assert_eq!((packet_id, packet.read_bytes(3)), (0x00, Some(vec![1, 2, 3])));
+        let (packet_id, mut packet) = buffer.read_mc_packet(Some(256)).unwrap();
+        assert_eq!((packet_id, packet.read_u8(), packet.remaining()), (0x2A, Some(4), 0));
+
+        // A packet that hasn't been fully received leaves the position alone
+        let mut buffer = Buffer::wrap(vec![0x05, 0x01, 0x02]);
+        assert!(buffer.read_mc_packet(None).is_none());
+        assert_eq!(buffer.get_position(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "deflate")]
+    fn minecraft_compressed_packets() {
+        let data = vec![0x55; 1000];
+        let mut buffer = Buffer::new();
+        buffer.write_mc_packet(0x10, &data, Some(256));
+        assert!(buffer.len() < 100);
+
+        let (packet_id, mut packet) = buffer.read_mc_packet(Some(256)).unwrap();
+        assert_eq!((packet_id, packet.read_bytes(1000)), (0x10, Some(data)));
+
+        // Compressed packets smaller than the threshold are rejected
+        buffer.set_position(0);
+        assert!(buffer.read_mc_packet(Some(2000)).is_none());
+    }
+
+    //#endregion Minecraft tests
+
+    //#region NBT tests
+
+    #[test]
+    fn nbt_java() {
+        use super::Nbt::{NbtFormat, NbtTag};
+
+        let mut buffer = Buffer::wrap(b"\x0A\x00\x0Bhello world\x08\x00\x04name\x00\x09Bananrama\x00".to_vec());
+        let (name, tag) = buffer.read_nbt(NbtFormat::Java).unwrap();
+        assert_eq!(name, "hello world");
+        assert_eq!(tag.get("name").and_then(NbtTag::as_str), Some("Bananrama"));
+
+        // NUL and characters past U+FFFF use Java's modified UTF-8
+        let mut buffer = Buffer::new();
+        buffer.write_nbt_nameless(NbtFormat::Java, &NbtTag::String("a\0😀".to_string()));
+        assert_eq!(buffer.as_slice(), [0x08, 0x00, 0x09, b'a', 0xC0, 0x80, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]);
+        assert_eq!(buffer.read_nbt_nameless(NbtFormat::Java), Some(NbtTag::String("a\0😀".to_string())));
+
+        let mixed = NbtTag::List(vec![NbtTag::Byte(1), NbtTag::Short(2)]);
+        assert_eq!(buffer.write_nbt(NbtFormat::Java, "", &mixed), None);
+        assert_eq!(Buffer::wrap(vec![0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]).read_nbt(NbtFormat::Java), None);
+    }
+
+    #[test]
+    fn nbt_round_trip() {
+        use super::Nbt::{NbtFormat, NbtTag};
+
+        let tag = NbtTag::Compound(vec![
+            ("byte".to_string(), NbtTag::Byte(-1)),
+            ("short".to_string(), NbtTag::Short(300)),
+            ("int".to_string(), NbtTag::Int(-70000)),
+            ("long".to_string(), NbtTag::Long(i64::MIN)),
+            ("float".to_string(), NbtTag::Float(0.5)),
+            ("double".to_string(), NbtTag::Double(-2.25)),
+            ("bytes".to_string(), NbtTag::ByteArray(vec![-128, 0, 127])),
+            ("list".to_string(), NbtTag::List(vec![NbtTag::Compound(vec![]), NbtTag::Compound(vec![])])),
+            ("empty".to_string(), NbtTag::List(vec![])),
+            ("ints".to_string(), NbtTag::IntArray(vec![i32::MIN, 0, i32::MAX])),
+            ("longs".to_string(), NbtTag::LongArray(vec![-1, 1])),
+        ]);
+        for format in [NbtFormat::Java, NbtFormat::Bedrock, NbtFormat::BedrockNetwork] {
+            let mut buffer = Buffer::new();
+            buffer.write_nbt(format, "root", &tag);
+            assert_eq!(buffer.read_nbt(format), Some(("root".to_string(), tag.clone())));
+            assert_eq!(buffer.remaining(), 0);
+        }
+
+        let mut buffer = Buffer::new();
+        buffer.write_nbt_nameless(NbtFormat::BedrockNetwork, &NbtTag::Int(-70000));
+        assert_eq!(buffer.as_slice(), [0x03, 0xDF, 0xC5, 0x08]);
+    }
+
+    #[test]
+    #[cfg(feature = "deflate")]
+    fn nbt_gzip_file() {
+        use super::Nbt::{read_nbt_file, write_nbt_file, NbtFormat, NbtTag};
+
+        let path = std::env::temp_dir().join(format!("muscleman-nbt-{}.dat", std::process::id()));
+        let tag = NbtTag::Compound(vec![("Data".to_string(), NbtTag::Compound(vec![("Time".to_string(), NbtTag::Long(24000))]))]);
+        write_nbt_file(&path, NbtFormat::Java, "", &tag).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bytes[..2], [0x1F, 0x8B]);
+        let (_, read) = read_nbt_file(&path, NbtFormat::Java).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read.get("Data").and_then(|data| data.get("Time")).and_then(NbtTag::as_i64), Some(24000));
+    }
+
+    //#endregion NBT tests
+
+    //#region UUID and address tests
+
+    #[test]
+    fn uuid_bytes() {
+        let rfc = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF];
+        let mut buffer = Buffer::new();
+        buffer.write_uuid_bytes(&rfc);
+        buffer.write_guid_bytes(&rfc);
+        assert_eq!(buffer.as_slice()[16..], [
+            0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF,
+        ]);
+        assert_eq!(buffer.read_uuid_bytes(), Some(rfc));
+        assert_eq!(buffer.read_guid_bytes(), Some(rfc));
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn uuid_values() {
+        let uuid = uuid::Uuid::parse_str("00112233-4455-6677-8899-aabbccddeeff").unwrap();
+        let mut buffer = Buffer::new();
+        buffer.write_uuid(&uuid);
+        buffer.write_guid(&uuid);
+        assert_eq!(buffer.as_slice()[16..20], uuid.to_bytes_le()[..4]);
+        assert_eq!(buffer.read_uuid(), Some(uuid));
+        assert_eq!(buffer.read_guid(), Some(uuid));
+    }
+
+    #[test]
+    fn socket_addresses() {
+        use super::ByteOrder::ByteOrder;
+        use core::net::{Ipv6Addr, SocketAddr};
+
+        let v4: SocketAddr = "192.168.1.2:25565".parse().unwrap();
+        let v6: SocketAddr = "[::1]:19132".parse().unwrap();
+        let mut buffer = Buffer::new();
+        buffer.write_socket_addr(v4, ByteOrder::BigEndian);
+        buffer.write_socket_addr(v6, ByteOrder::LittleEndian);
+        assert_eq!(buffer.as_slice()[..7], [4, 192, 168, 1, 2, 0x63, 0xDD]);
+        assert_eq!(buffer.as_slice()[24..], [0xBC, 0x4A]);
+        assert_eq!(buffer.read_socket_addr(ByteOrder::BigEndian), Some(v4));
+        assert_eq!(buffer.read_socket_addr(ByteOrder::LittleEndian), Some(v6));
+
+        buffer.write_ipv6(Ipv6Addr::LOCALHOST);
+        assert_eq!(buffer.read_ipv6(), Some(Ipv6Addr::LOCALHOST));
+        assert_eq!(Buffer::wrap(vec![5, 0, 0, 0, 0, 0, 0]).read_socket_addr(ByteOrder::BigEndian), None);
+
+        let mut out = [0; 10];
+        let mut writer = Buffer::wrap_empty(&mut out[..]);
+        assert_eq!(writer.write_socket_addr(v6, ByteOrder::BigEndian), None);
+        assert_eq!(writer.len(), 0);
+    }
+
+    //#endregion UUID and address tests
+
+    //#region Timestamp tests
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn timestamps() {
+        use super::ByteOrder::ByteOrder;
+        use super::Time::TimeFormat;
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let time = UNIX_EPOCH + Duration::new(1_709_214_330, 123_456_789);
+        let mut buffer = Buffer::new();
+        buffer.write_timestamp(UNIX_EPOCH, TimeFormat::Ntp64);
+        buffer.write_timestamp(UNIX_EPOCH, TimeFormat::FileTime);
+        buffer.write_timestamp(time, TimeFormat::DosDateTime);
+        assert_eq!(buffer.as_slice(), [
+            0x83, 0xAA, 0x7E, 0x80, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x9D, 0xB1, 0xDE, 0xD5, 0x3E, 0x80, 0x00,
+            0x6D, 0xAF, 0x58, 0x5D,
+        ]);
+        assert_eq!(buffer.read_timestamp(TimeFormat::Ntp64), Some(UNIX_EPOCH));
+        assert_eq!(buffer.read_timestamp(TimeFormat::FileTime), Some(UNIX_EPOCH));
+        assert_eq!(buffer.read_timestamp(TimeFormat::DosDateTime), Some(UNIX_EPOCH + Duration::from_secs(1_709_214_330)));
+
+        let mut buffer = Buffer::new();
+        buffer.set_byte_order(ByteOrder::LittleEndian);
+        for format in [TimeFormat::UnixSeconds32, TimeFormat::UnixSeconds64] {
+            buffer.write_timestamp(time, format);
+            assert_eq!(buffer.read_timestamp(format), Some(UNIX_EPOCH + Duration::from_secs(1_709_214_330)));
+        }
+        buffer.write_timestamp(time, TimeFormat::UnixMillis);
+        assert_eq!(buffer.read_timestamp(TimeFormat::UnixMillis), Some(UNIX_EPOCH + Duration::from_millis(1_709_214_330_123)));
+        for format in [TimeFormat::UnixNanos, TimeFormat::Ntp64] {
+            buffer.write_timestamp(time, format);
+            assert_eq!(buffer.read_timestamp(format), Some(time));
+        }
+        let before = UNIX_EPOCH - Duration::from_millis(1500);
+        buffer.write_timestamp(before, TimeFormat::UnixMillis);
+        assert_eq!(buffer.read_timestamp(TimeFormat::UnixMillis), Some(before));
+
+        // Times outside a format's range, and impossible DOS dates
+        assert_eq!(buffer.write_timestamp(before, TimeFormat::UnixSeconds32), None);
+        assert_eq!(buffer.write_timestamp(UNIX_EPOCH + Duration::from_secs(1 << 32), TimeFormat::Ntp64), None);
+        assert_eq!(buffer.write_timestamp(UNIX_EPOCH, TimeFormat::DosDateTime), None);
+        assert_eq!(buffer.remaining(), 0);
+        let mut dos = Buffer::wrap(vec![0x00, 0x00, 0x58, 0x5E]);
+        assert_eq!(dos.read_timestamp(TimeFormat::DosDateTime), None);
+    }
+
+    //#endregion Timestamp tests
+
+    //#region Padding tests
+
+    #[test]
+    fn alignment_and_padding() {
+        let mut buffer = Buffer::new();
+        buffer.write_u8(1);
+        buffer.align_write_to(4);
+        buffer.write_u16(2);
+        buffer.write_padding(3, 0xFF);
+        buffer.align_write_to(8);
+        buffer.write_zeros(2);
+        assert_eq!(buffer.as_slice(), [1, 0, 0, 0, 0, 2, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+        // A nested record aligned from its own start
+        buffer.set_alignment_base(buffer.len());
+        buffer.write_u8(3);
+        buffer.align_write_to(4);
+        assert_eq!(buffer.len(), 22);
+        assert_eq!(buffer.align_write_to(0), None);
+
+        buffer.set_alignment_base(0);
+        buffer.read_u8();
+        buffer.align_to(4);
+        assert_eq!(buffer.get_position(), 4);
+        buffer.read_u16();
+        assert_eq!(buffer.skip_padding_strict(3), None);
+        assert_eq!(buffer.get_position(), 6);
+        buffer.skip_padding(3);
+        buffer.align_to(8);
+        assert_eq!(buffer.skip_padding_strict(2), Some(()));
+        buffer.set_alignment_base(20);
+        assert_eq!(buffer.align_to(4), None);
+        buffer.set_alignment_base(18);
+        assert_eq!(buffer.read_u8(), Some(3));
+        buffer.align_to(4);
+        assert_eq!(buffer.remaining(), 0);
+    }
+
+    //#endregion Padding tests
+
+    //#region Sub-reader tests
+
+    #[test]
+    fn sub_readers() {
+        use super::ByteOrder::ByteOrder;
+
+        let mut buffer = Buffer::new();
+        buffer.set_byte_order(ByteOrder::LittleEndian);
+        buffer.write_bytes(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+
+        let mut body = buffer.sub_reader(4).unwrap();
+        assert_eq!(body.read_u16(), Some(0x0201));
+        assert_eq!(body.get_position(), 2);
+        assert_eq!(body.read_u32(), None);
+        assert_eq!(body.finish(), 2);
+        assert_eq!(buffer.get_position(), 4);
+
+        let mut body = buffer.sub_reader(2).unwrap();
+        assert_eq!(body.read_u8(), Some(0x05));
+        assert_eq!(body.expect_end(), None);
+        assert_eq!(buffer.remaining(), 0);
+        assert!(buffer.sub_reader(1).is_none());
+
+        buffer.set_position(0);
+        let mut body = buffer.sub_reader(2).unwrap();
+        body.read_u16();
+        assert_eq!(body.expect_end(), Some(()));
+    }
+
+    //#endregion Sub-reader tests
+
+    //#region Atomic read tests
+
+    #[test]
+    fn atomic_reads() {
+        use super::Protobuf::WireType;
+
+        // A length prefix promising more than is there
+        let mut buffer = Buffer::new();
+        buffer.write_u32(10);
+        buffer.write_bytes(b"abc");
+        assert_eq!(buffer.read_string_with_length(), None);
+        assert_eq!(buffer.get_position(), 0);
+
+        // A VarInt cut short
+        let mut buffer = Buffer::wrap(vec![0x80, 0x80]);
+        assert_eq!(buffer.read_varint(), None);
+        assert_eq!(buffer.read_mc_varint(), None);
+        assert_eq!(buffer.get_position(), 0);
+
+        // A tag followed by a truncated length-delimited field
+        let mut buffer = Buffer::wrap(vec![0x0A, 0x05, 0x01]);
+        assert_eq!(buffer.read_tag(), Some((1, WireType::LengthDelimited)));
+        assert_eq!(buffer.read_length_delimited(), None);
+        assert_eq!(buffer.skip_field(1, WireType::LengthDelimited), None);
+        assert_eq!(buffer.get_position(), 1);
+
+        // A MessagePack string that isn't valid UTF-8
+        let mut buffer = Buffer::wrap(vec![0xA2, 0xFF, 0xFE]);
+        assert_eq!(buffer.read_msgpack_str(), None);
+        assert_eq!(buffer.read_msgpack_value(), None);
+        assert_eq!(buffer.get_position(), 0);
+        assert_eq!(buffer.read_msgpack_bin(), None);
+    }
+
+    // Checks that a read fails without moving on every truncation of the bytes, and reads them all when whole
+    fn assert_atomic<T>(bytes: &[u8], read: impl Fn(&mut Buffer<&[u8]>) -> Option<T>) {
+        for end in 0..bytes.len() {
+            let mut buffer = Buffer::wrap(&bytes[..end]);
+            assert!(read(&mut buffer).is_none(), "read {} of {} bytes", end, bytes.len());
+            assert_eq!(buffer.get_position(), 0);
+        }
+        let mut buffer = Buffer::wrap(bytes);
+        assert!(read(&mut buffer).is_some());
+        assert_eq!(buffer.remaining(), 0);
+    }
+
+    #[test]
+    fn atomic_reads_of_truncated_formats() {
+        use std::net::SocketAddr;
+
+        use super::ByteOrder::ByteOrder;
+        use super::Nbt::{NbtFormat, NbtTag};
+        use super::Schema::Schema;
+        use super::Value::Value;
+
+        let mut buffer = Buffer::new();
+        buffer.write_cbor_value(&Value::Array(vec![Value::String("cbor".to_string()), Value::Int(-1000)]));
+        assert_atomic(buffer.as_slice(), |buffer| buffer.read_cbor_value());
+
+        let tree = NbtTag::Compound(vec![("name".to_string(), NbtTag::String("nbt".to_string())), ("count".to_string(), NbtTag::Int(3))]);
+        let mut buffer = Buffer::new();
+        buffer.write_nbt(NbtFormat::Java, "root", &tree);
+        assert_atomic(buffer.as_slice(), |buffer| buffer.read_nbt(NbtFormat::Java));
+
+        let mut buffer = Buffer::new();
+        buffer.write_mc_string("minecraft:stone", 32767);
+        assert_atomic(buffer.as_slice(), |buffer| buffer.read_mc_identifier());
+        let mut buffer = Buffer::new();
+        buffer.write_mc_bitset(&[1, 2]);
+        assert_atomic(buffer.as_slice(), |buffer| buffer.read_mc_bitset());
+
+        let schema = Schema::parse("id: u16le\nname: string(prefix=u8)").unwrap();
+        let value = Value::Struct(vec![("id".to_string(), Value::UInt(7)), ("name".to_string(), Value::String("schema".to_string()))]);
+        let mut buffer = Buffer::new();
+        schema.encode(&value, &mut buffer).unwrap();
+        assert_atomic(buffer.as_slice(), |buffer| schema.decode(buffer).ok());
+
+        let addr: SocketAddr = "[::1]:25565".parse().unwrap();
+        let mut buffer = Buffer::new();
+        buffer.write_socket_addr(addr, ByteOrder::LittleEndian);
+        assert_atomic(buffer.as_slice(), |buffer| buffer.read_socket_addr(ByteOrder::LittleEndian));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn atomic_reads_of_truncated_timestamps() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        use super::Time::TimeFormat;
+
+        let mut buffer = Buffer::new();
+        buffer.write_timestamp(UNIX_EPOCH + Duration::from_millis(1500), TimeFormat::Ntp64);
+        buffer.write_timestamp(UNIX_EPOCH + Duration::from_secs(400_000_000), TimeFormat::DosDateTime);
+        assert_atomic(&buffer.as_slice()[..8], |buffer| buffer.read_timestamp(TimeFormat::Ntp64));
+        assert_atomic(&buffer.as_slice()[8..], |buffer| buffer.read_timestamp(TimeFormat::DosDateTime));
+
+        // An impossible DOS date is read whole, then rejected
+        let mut buffer = Buffer::wrap(vec![0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(buffer.read_timestamp(TimeFormat::DosDateTime), None);
+        assert_eq!(buffer.get_position(), 0);
+    }
+
+    #[test]
+    fn transactions() {
+        let mut buffer = Buffer::new();
+        buffer.write_u16(1);
+
+        // A failed transaction undoes its reads and writes
+        let result = buffer.transaction(|buffer| {
+            buffer.read_u16()?;
+            buffer.write_u32(2)?;
+            buffer.read_u64()
+        });
+        assert_eq!(result, None);
+        assert_eq!(buffer.get_position(), 0);
+        assert_eq!(buffer.as_slice(), [0, 1]);
+
+        // A successful one keeps them, and an inner failure only undoes its own
+        let result = buffer.transaction(|buffer| {
+            let value = buffer.read_u16()?;
+            buffer.write_u8(3)?;
+            assert_eq!(buffer.transaction(|buffer| buffer.write_u8(4).and(buffer.read_u32())), None);
+            assert_eq!(buffer.mark, Some(0));
+            Some(value)
+        });
+        assert_eq!(result, Some(1));
+        assert_eq!(buffer.get_position(), 2);
+        assert_eq!(buffer.as_slice(), [0, 1, 3]);
+        assert_eq!(buffer.mark, None);
+
+        // Writes into fixed-size storage are dropped too
+        let mut buffer = Buffer::wrap_empty([0; 4]);
+        assert_eq!(buffer.transaction(|buffer| buffer.write_u16(5).and(buffer.write_u32(6))), None);
+        assert_eq!(buffer.len(), 0);
+
+        // Read-only buffers roll back their reads
+        let bytes = [0x00, 0x01, 0x02];
+        let mut buffer = Buffer::wrap(&bytes[..]);
+        assert_eq!(buffer.transaction(|buffer| buffer.read_u16().and(buffer.read_u16())), None);
+        assert_eq!(buffer.get_position(), 0);
+        let mut body = buffer.sub_reader(3).unwrap();
+        assert_eq!(body.transaction(|body| body.read_u8().and(body.read_u32())), None);
+        assert_eq!(body.read_u8(), Some(0x00));
+    }
+
+    //#endregion Atomic read tests
+
+    //#region Pod tests
+
+    #[test]
+    fn pod_values() {
+        use super::Pod::{Pod, I16Le, U16Be, U32Le};
+
+        #[repr(C)]
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Record {
+            kind: U16Be,
+            delta: I16Le,
+            size: U32Le,
+        }
+
+        // SAFETY: repr(C), no padding, and every field is Pod
+        unsafe impl Pod for Record {}
+
+        let record = Record { kind: U16Be::new(7), delta: I16Le::new(-2), size: 0x01020304.into() };
+        let mut buffer = Buffer::new();
+        buffer.write_pod(&record);
+        buffer.write_pod(&[1u8, 2]);
+        assert_eq!(buffer.as_slice(), [0x00, 0x07, 0xFE, 0xFF, 0x04, 0x03, 0x02, 0x01, 1, 2]);
+
+        assert_eq!(buffer.view::<Record>(), Some(&record));
+        assert_eq!(buffer.view::<Record>(), None);
+        assert_eq!(buffer.get_position(), 8);
+        assert_eq!(buffer.read_pod::<[u8; 2]>(), Some([1, 2]));
+
+        buffer.set_position(1);
+        let mut copied = buffer.read_pod::<Record>().unwrap();
+        assert_eq!(copied.kind.get(), 0x07FE);
+        copied.size.set(5);
+        assert_eq!(u32::from(copied.size), 5);
+        assert_eq!(format!("{:?}", record.delta), "-2");
+    }
+
+    //#endregion Pod tests
+
+    //#region Hex and Base64 tests
+
+    #[test]
+    fn hex_text() {
+        let mut buffer = Buffer::from_hex("0x31 32\n0X6A6b\t00").unwrap();
+        assert_eq!(buffer.as_slice(), [0x31, 0x32, 0x6A, 0x6B, 0x00]);
+        assert_eq!(buffer.to_hex(), "31326a6b00");
+        assert!(Buffer::from_hex("12 3").is_none());
+        assert!(Buffer::from_hex("0x12 0x3").is_none());
+        assert!(Buffer::from_hex("zz").is_none());
+        assert_eq!(Buffer::from_hex("").map(|buffer| buffer.len()), Some(0));
+
+        assert_eq!(buffer.read_hex_string(3), None);
+        assert_eq!(buffer.read_hex_string(2), Some(vec![0x12]));
+        assert_eq!(buffer.read_hex_string(2), None);
+        assert_eq!(buffer.get_position(), 2);
+
+        let mut buffer = Buffer::new();
+        buffer.write_hex_string(&[0xAB, 0x01]);
+        assert_eq!(buffer.as_slice(), b"ab01");
+    }
+
+    #[test]
+    fn base64_text() {
+        use super::Base64::Base64Variant;
+
+        // The test vectors from RFC 4648
+        let vectors = ["", "Zg==", "Zm8=", "Zm9v", "Zm9vYg==", "Zm9vYmE=", "Zm9vYmFy"];
+        for (length, encoded) in vectors.iter().enumerate() {
+            let buffer = Buffer::from_base64(encoded, Base64Variant::Standard).unwrap();
+            assert_eq!(buffer.as_slice(), &b"foobar"[..length]);
+            assert_eq!(buffer.to_base64(Base64Variant::Standard), *encoded);
+            assert_eq!(buffer.to_base64(Base64Variant::StandardNoPad), encoded.trim_end_matches('='));
+        }
+
+        let buffer = Buffer::from_base64("+/8=", Base64Variant::Standard).unwrap();
+        assert_eq!(buffer.as_slice(), [0xFB, 0xFF]);
+        assert_eq!(buffer.to_base64(Base64Variant::UrlSafe), "-_8=");
+        assert_eq!(buffer.to_base64(Base64Variant::UrlSafeNoPad), "-_8");
+        assert_eq!(Buffer::from_base64("-_8", Base64Variant::UrlSafeNoPad).unwrap().as_slice(), [0xFB, 0xFF]);
+        assert_eq!(Buffer::from_base64("Zm9v\nYmFy\n", Base64Variant::Standard).unwrap().as_slice(), b"foobar");
+
+        assert!(Buffer::from_base64("-_8=", Base64Variant::Standard).is_none());
+        assert!(Buffer::from_base64("Zg=a", Base64Variant::Standard).is_none());
+        assert!(Buffer::from_base64("Zg=", Base64Variant::Standard).is_none());
+        assert!(Buffer::from_base64("Zm9v=", Base64Variant::Standard).is_none());
+        assert!(Buffer::from_base64("Z", Base64Variant::Standard).is_none());
+        assert!(Buffer::from_base64("Zh==", Base64Variant::Standard).is_none());
+    }
+
+    //#endregion Hex and Base64 tests
+
+    //#region Standard trait tests
+
+    #[test]
+    fn standard_traits() {
+        use std::collections::HashSet;
+
+        use super::ByteOrder::ByteOrder;
+
+        let mut buffer = Buffer::from("ab");
+        buffer.read_u8();
+        let copy = buffer.clone();
+        assert_eq!(copy.get_position(), 1);
+        assert_eq!(copy, Buffer::from(&b"ab"[..]));
+        assert_eq!(copy, Buffer::wrap(&b"ab"[..]));
+        assert_ne!(copy, Buffer::from(vec![b'a']));
+
+        let mut set = HashSet::new();
+        set.insert(buffer);
+        assert!(set.contains(&Buffer::from("ab")));
+
+        let mut buffer: Buffer = (1..=3).collect();
+        buffer.extend([4, 5]);
+        buffer.extend(&[6]);
+        assert_eq!(buffer.len(), 6);
+        assert_eq!(buffer.read_u8(), Some(1));
+        assert_eq!(buffer.as_ref(), [1, 2, 3, 4, 5, 6]);
+        assert_eq!((&buffer).into_iter().sum::<u8>(), 21);
+        assert_eq!(buffer.clone().into_iter().last(), Some(6));
+        assert_eq!(Vec::from(buffer), vec![1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(ByteOrder::default(), Buffer::DEFAULT_BYTE_ORDER);
+        assert_eq!(Buffer::default(), Buffer::from(""));
+    }
+
+    //#endregion Standard trait tests
+}
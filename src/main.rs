@@ -0,0 +1,211 @@
+//! `muscleman` command-line inspector.
+//!
+//! ```text
+//! muscleman dump <file> [--start N] [--end N] [--width N] [--group N] [--annotate]
+//! muscleman decode <file> <schema> [--offset N]
+//! muscleman encode <schema> <json> [--output FILE]
+//! muscleman read <file> <type>@<offset>...
+//! ```
+//!
+//! Schemas ending in `.json` are parsed as JSON descriptions, anything else as text descriptions.
+
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use muscleman::Buffer::Buffer;
+use muscleman::HexDump::HexDumpOptions;
+use muscleman::Schema::{FieldType, Schema};
+use muscleman::Value::Value;
+
+const USAGE: &str = "usage:
+    muscleman dump <file> [--start N] [--end N] [--width N] [--group N] [--annotate]
+    muscleman decode <file> <schema> [--offset N]
+    muscleman encode <schema> <json> [--output FILE]
+    muscleman read <file> <type>@<offset>...";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("muscleman: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let (command, args) = args.split_first().ok_or(USAGE)?;
+    let (positional, options, flags) = split_options(args)?;
+    match (command.as_str(), positional.as_slice()) {
+        ("dump", [file]) => dump(file, &options, &flags),
+        ("decode", [file, schema]) => decode(file, schema, &options),
+        ("encode", [schema, json]) => encode(schema, json, &options),
+        ("read", [file, probes @ ..]) if !probes.is_empty() => read(file, probes),
+        ("help" | "--help" | "-h", _) => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+// `--name value` pairs given on the command line
+type Options<'a> = Vec<(&'a str, &'a String)>;
+
+// The options that are given alone, without a value
+const FLAGS: &[&str] = &["annotate"];
+
+// Splits `--name value` options and `--name` flags from positional arguments
+fn split_options(args: &[String]) -> Result<(Vec<&String>, Options<'_>, Vec<&str>), String> {
+    let mut positional = Vec::new();
+    let mut options = Vec::new();
+    let mut flags = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--") {
+            Some(name) if FLAGS.contains(&name) => flags.push(name),
+            Some(name) => options.push((name, args.next().ok_or(format!("missing value for --{}", name))?)),
+            None => positional.push(arg),
+        }
+    }
+    Ok((positional, options, flags))
+}
+
+fn option_number(options: &[(&str, &String)], name: &str) -> Result<Option<usize>, String> {
+    match options.iter().find(|(option, _)| *option == name) {
+        Some((_, value)) => parse_number(value).map(Some),
+        None => Ok(None),
+    }
+}
+
+// Parses a decimal or `0x` prefixed hexadecimal number
+fn parse_number(text: &str) -> Result<usize, String> {
+    let number = match text.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => text.parse(),
+    };
+    number.map_err(|_| format!("invalid number {}", text))
+}
+
+fn load_file(path: &str) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|e| format!("{}: {}", path, e))
+}
+
+// Creates a buffer reading the data from `offset` onwards
+fn buffer_at(data: &[u8], offset: usize) -> Result<Buffer<&[u8]>, String> {
+    let data = data.get(offset..).ok_or(format!("offset {:#x} is past the end of the file", offset))?;
+    Ok(Buffer::wrap(data))
+}
+
+fn load_schema(path: &str) -> Result<Schema, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let schema = if path.ends_with(".json") { Schema::from_json(&text) } else { Schema::parse(&text) };
+    schema.map_err(|e| format!("{}: {}", path, e))
+}
+
+fn dump(file: &str, options: &[(&str, &String)], flags: &[&str]) -> Result<(), String> {
+    let data = load_file(file)?;
+    let buffer = buffer_at(&data, 0)?;
+    let defaults = HexDumpOptions::new();
+    let start = option_number(options, "start")?.unwrap_or(0);
+    let end = option_number(options, "end")?.unwrap_or(buffer.len());
+    let dump_options = HexDumpOptions {
+        range: Some(start..end),
+        width: option_number(options, "width")?.unwrap_or(defaults.width),
+        group: option_number(options, "group")?.unwrap_or(defaults.group),
+        annotate: flags.contains(&"annotate"),
+    };
+    print!("{}", buffer.hexdump_with_options(&dump_options));
+    Ok(())
+}
+
+fn decode(file: &str, schema: &str, options: &[(&str, &String)]) -> Result<(), String> {
+    let schema = load_schema(schema)?;
+    let data = load_file(file)?;
+    let mut buffer = buffer_at(&data, option_number(options, "offset")?.unwrap_or(0))?;
+    let value = schema.decode(&mut buffer).map_err(|e| e.to_string())?;
+    println!("{}", value.to_json_pretty());
+    if buffer.remaining() > 0 {
+        eprintln!("muscleman: {} trailing bytes were not decoded", buffer.remaining());
+    }
+    Ok(())
+}
+
+fn encode(schema: &str, json: &str, options: &[(&str, &String)]) -> Result<(), String> {
+    let schema = load_schema(schema)?;
+    let text = fs::read_to_string(json).map_err(|e| format!("{}: {}", json, e))?;
+    let value = Value::from_json(&text).map_err(|e| format!("{}: {}", json, e))?;
+    let mut buffer = Buffer::new();
+    schema.encode(&value, &mut buffer).map_err(|e| e.to_string())?;
+    match options.iter().find(|(option, _)| *option == "output") {
+        Some((_, path)) => fs::write(path, buffer.get_data()).map_err(|e| format!("{}: {}", path, e)),
+        None => io::stdout().write_all(&buffer.get_data()).map_err(|e| e.to_string()),
+    }
+}
+
+fn read(file: &str, probes: &[&String]) -> Result<(), String> {
+    let data = load_file(file)?;
+    for probe in probes {
+        println!("{} = {}", probe, read_probe(&data, probe)?.to_json());
+    }
+    Ok(())
+}
+
+// Decodes the value a `<type>@<offset>` probe points at
+fn read_probe(data: &[u8], probe: &str) -> Result<Value, String> {
+    let (field_type, offset) = probe.split_once('@').ok_or(format!("expected <type>@<offset>, found {}", probe))?;
+    let field_type = FieldType::parse(field_type).map_err(|e| e.to_string())?;
+    let mut buffer = buffer_at(data, parse_number(offset)?)?;
+    field_type.decode(&mut buffer, &[]).map_err(|e| format!("{}: {}", probe, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Turns string literals into owned arguments
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn split_options_and_flags() {
+        let argv = args(&["file.bin", "--start", "0x10", "--annotate", "--width", "8"]);
+        let (positional, options, flags) = split_options(&argv).unwrap();
+        assert_eq!(positional, [&argv[0]]);
+        assert_eq!(option_number(&options, "start"), Ok(Some(16)));
+        assert_eq!(option_number(&options, "width"), Ok(Some(8)));
+        assert_eq!(option_number(&options, "group"), Ok(None));
+        assert_eq!(flags, ["annotate"]);
+
+        assert_eq!(split_options(&args(&["file.bin", "--end"])).unwrap_err(), "missing value for --end");
+        let argv = args(&["--end", "ten"]);
+        let (_, options, _) = split_options(&argv).unwrap();
+        assert_eq!(option_number(&options, "end"), Err("invalid number ten".to_string()));
+    }
+
+    #[test]
+    fn run_rejects_bad_usage() {
+        assert_eq!(run(&[]), Err(USAGE.to_string()));
+        assert_eq!(run(&args(&["dump"])), Err(USAGE.to_string()));
+        assert_eq!(run(&args(&["read", "file.bin"])), Err(USAGE.to_string()));
+        assert_eq!(run(&args(&["frobnicate", "file.bin"])), Err(USAGE.to_string()));
+    }
+
+    #[test]
+    fn read_probes() {
+        let data = [0x01, 0x00, 0x2A, 0x02, b'h', b'i'];
+        assert_eq!(read_probe(&data, "u16be@0"), Ok(Value::UInt(256)));
+        assert_eq!(read_probe(&data, "u16le@0"), Ok(Value::UInt(1)));
+        assert_eq!(read_probe(&data, "u8@0x2"), Ok(Value::UInt(42)));
+        assert_eq!(read_probe(&data, "string(prefix=u8)@3"), Ok(Value::String("hi".to_string())));
+
+        assert_eq!(read_probe(&data, "u16"), Err("expected <type>@<offset>, found u16".to_string()));
+        assert_eq!(read_probe(&data, "u8@7"), Err("offset 0x7 is past the end of the file".to_string()));
+        assert_eq!(read_probe(&data, "u32be@4"), Err("u32be@4: unexpected end of buffer".to_string()));
+        assert!(read_probe(&data, "u24@0").is_err());
+    }
+}